@@ -0,0 +1,139 @@
+use crate::helpers::ocr;
+use serde_json::Value;
+use std::fmt;
+
+/// The answer of a part, compared against stored answers in the way that
+/// fits its kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    /// A picture drawn with characters, one string per row.
+    Grid(Vec<String>),
+}
+impl Answer {
+    /// A grid from its rows, separated by newlines.
+    pub fn grid(text: &str) -> Self {
+        Self::Grid(text.lines().map(str::to_string).collect())
+    }
+
+    /// Reads a stored answer. Arrays are grids of one line per string.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(num) => Some(
+                num.as_i64()
+                    .map_or_else(|| Self::Text(num.to_string()), Self::Number),
+            ),
+            Value::String(str) => Some(Self::Text(str.clone())),
+            Value::Bool(bool) => Some(Self::Text(bool.to_string())),
+            Value::Array(vec) => Some(Self::Grid(
+                vec.iter()
+                    .map(|v| {
+                        if let Value::String(s) = v {
+                            s.clone()
+                        } else {
+                            v.to_string()
+                        }
+                    })
+                    .collect(),
+            )),
+            Value::Null | Value::Object(_) => None,
+        }
+    }
+    /// The answer as stored in the results file, a grid being an array of lines.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Number(num) => Value::from(*num),
+            Self::Text(text) => Value::from(text.as_str()),
+            Self::Grid(rows) => rows.iter().map(|row| Value::from(row.as_str())).collect(),
+        }
+    }
+
+    /// Whether this is the `expected` answer. Grids match regardless of
+    /// trailing whitespace and of empty lines around them, a number matches
+    /// its digits stored as text, and letters match the grid drawing them.
+    pub fn matches(&self, expected: &Self) -> bool {
+        match (self, expected) {
+            (Self::Number(num), Self::Number(expected)) => num == expected,
+            (Self::Text(text), Self::Grid(rows)) | (Self::Grid(rows), Self::Text(text))
+                if ocr::read_letters(rows).as_ref() == Some(text) =>
+            {
+                true
+            }
+            (Self::Grid(_), _) | (_, Self::Grid(_)) => self.rows() == expected.rows(),
+            _ => self.to_string() == expected.to_string(),
+        }
+    }
+    /// The rows of the answer without trailing whitespace, leaving out
+    /// empty rows at the start and the end.
+    fn rows(&self) -> Vec<String> {
+        let text = self.to_string();
+        let rows: Vec<_> = text.lines().map(str::trim_end).collect();
+        let start = rows.iter().position(|row| !row.is_empty());
+        let end = rows.iter().rposition(|row| !row.is_empty());
+        match (start, end) {
+            (Some(start), Some(end)) => rows[start..=end]
+                .iter()
+                .map(|&row| row.to_string())
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(num) => write!(f, "{num}"),
+            Self::Text(text) => write!(f, "{text}"),
+            Self::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+macro_rules! from_number {
+    ($($type:ty),*) => {
+        $(impl From<$type> for Answer {
+            fn from(num: $type) -> Self {
+                i64::try_from(num).map_or_else(|_| Self::Text(num.to_string()), Self::Number)
+            }
+        })*
+    };
+}
+from_number!(i32, u32, i64, u64, usize, isize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    #[test]
+    fn matching() {
+        assert!(Answer::from(42_usize).matches(&Answer::Number(42)));
+        assert!(Answer::from(42).matches(&Answer::Text("42".to_string())));
+        assert!(!Answer::from(42).matches(&Answer::Number(43)));
+        assert!(!Answer::Text("abc".to_string()).matches(&Answer::Text("abc ".to_string())));
+        let grid = Answer::grid("#..#\n.##.");
+        assert!(grid.matches(&Answer::grid("\n#..#  \n.##.\n\n")));
+        assert!(grid.matches(&Answer::Text("#..#\n.##.".to_string())));
+        assert!(!grid.matches(&Answer::grid("#..#\n.#..")));
+        let letters = Answer::grid("#..#\n#..#\n####\n#..#\n#..#\n#..#");
+        assert!(Answer::Text("H".to_string()).matches(&letters));
+        assert!(!Answer::Text("A".to_string()).matches(&letters));
+    }
+    #[test]
+    fn json() {
+        for value in [json!(12), json!("text"), json!(["#..#", ".##."])] {
+            assert_eq!(Answer::from_json(&value).unwrap().to_json(), value);
+        }
+        assert_eq!(
+            Answer::from_json(&json!(u64::MAX)),
+            Some(Answer::Text(u64::MAX.to_string()))
+        );
+        assert_eq!(Answer::from_json(&json!(null)), None);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::report::InputRun;
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::{collections::HashMap, fs, path::Path, time::Duration};
+
+/// Phases that got slower by less than this are never flagged, as the
+/// relative noise of very fast phases is large.
+const MIN_REGRESSION: Duration = Duration::from_micros(10);
+
+/// Phase timings by day, input name (with the variant appended when it isn't
+/// the default one, see [`InputRun::key`]) and phase name.
+pub type Baseline = HashMap<u8, HashMap<String, HashMap<String, Duration>>>;
+
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub day: u8,
+    pub input: String,
+    pub phase: &'static str,
+    pub old: Duration,
+    pub new: Duration,
+    pub regressed: bool,
+}
+impl Comparison {
+    /// Relative change in percent, positive when the phase got slower.
+    pub fn change(&self) -> f64 {
+        (self.new.as_secs_f64() / self.old.as_secs_f64() - 1.0) * 100.0
+    }
+}
+
+/// The phases of `run` whose parts finished. The times of failed and timed
+/// out parts say nothing about the solution, and neither does the parse time
+/// when no part finished, like when the input couldn't be parsed.
+fn finished_phases(run: &InputRun) -> impl Iterator<Item = (&'static str, Duration)> + '_ {
+    let finished = |i: usize| {
+        run.parts[i]
+            .as_ref()
+            .is_some_and(|part| part.status.finished())
+    };
+    run.phases().filter(move |&(phase, _)| match phase {
+        "part1" | "clone1" => finished(0),
+        "part2" | "clone2" => finished(1),
+        "both" => run
+            .parts
+            .iter()
+            .flatten()
+            .all(|part| part.status.finished()),
+        _ => (0..=1).any(finished),
+    })
+}
+
+/// Saves the phase timings of a run as nanoseconds, leaving out the phases
+/// of parts that didn't finish:
+/// `{ "1": { "default": { "parse": 1200, "part1": 800, "part2": 950 } } }`
+pub fn save(file: &Path, runs: &[InputRun]) -> Result<()> {
+    let mut days = Map::new();
+    for run in runs {
+        let inputs = days
+            .entry(run.day.to_string())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .unwrap();
+        let phases = finished_phases(run)
+            .map(|(phase, time)| {
+                let nanos = u64::try_from(time.as_nanos()).unwrap_or(u64::MAX);
+                (phase.to_string(), Value::from(nanos))
+            })
+            .collect();
+        inputs.insert(run.key(), Value::Object(phases));
+    }
+    let mut out = serde_json::to_string_pretty(&Value::Object(days))?;
+    out.push('\n');
+    fs::write(file, out)?;
+    Ok(())
+}
+
+pub fn load(file: &Path) -> Result<Baseline> {
+    let v: Value = serde_json::from_str(&fs::read_to_string(file)?)?;
+    let mut baseline = Baseline::new();
+    for (day, inputs) in v.as_object().context("Invalid baseline file")? {
+        let day: u8 = day.parse()?;
+        for (input, phases) in inputs
+            .as_object()
+            .context(format!("Invalid baseline for day {day}"))?
+        {
+            for (phase, nanos) in phases
+                .as_object()
+                .context(format!("Invalid baseline for day {day} input {input}"))?
+            {
+                let nanos = nanos
+                    .as_u64()
+                    .context(format!("Invalid {phase} time for day {day} input {input}"))?;
+                baseline
+                    .entry(day)
+                    .or_default()
+                    .entry(input.clone())
+                    .or_default()
+                    .insert(phase.clone(), Duration::from_nanos(nanos));
+            }
+        }
+    }
+    Ok(baseline)
+}
+
+/// Compares every phase that is in the baseline and finished in `runs`. A
+/// phase has regressed when it got slower by more than `threshold` percent.
+pub fn compare(runs: &[InputRun], baseline: &Baseline, threshold: f64) -> Vec<Comparison> {
+    let mut comparisons = vec![];
+    for run in runs {
+        let key = run.key();
+        let Some(phases) = baseline.get(&run.day).and_then(|d| d.get(&key)) else {
+            continue;
+        };
+        for (phase, new) in finished_phases(run) {
+            let Some(&old) = phases.get(phase) else {
+                continue;
+            };
+            let limit = old.mul_f64(1.0 + threshold / 100.0);
+            comparisons.push(Comparison {
+                day: run.day,
+                input: key.clone(),
+                phase,
+                old,
+                new,
+                regressed: new > limit && new.saturating_sub(old) > MIN_REGRESSION,
+            });
+        }
+    }
+    comparisons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        answer::Answer,
+        days::registry::DEFAULT_VARIANT,
+        report::{PartRun, Status},
+    };
+    fn run(status: Status) -> InputRun {
+        let part = |status| PartRun {
+            answer: Answer::Number(1),
+            expected: None,
+            time: Duration::from_micros(100),
+            clone_time: None,
+            stats: None,
+            allocations: None,
+            status,
+        };
+        InputRun {
+            day: 1,
+            input: "default".to_string(),
+            variant: DEFAULT_VARIANT,
+            parse_time: Duration::from_micros(100),
+            parse_stats: None,
+            parse_allocations: None,
+            parts: [Some(part(Status::Match)), Some(part(status))],
+            both_time: None,
+            both_stats: None,
+            both_allocations: None,
+        }
+    }
+    fn baseline(phases: &[(&str, u64)]) -> Baseline {
+        let phases = phases
+            .iter()
+            .map(|&(phase, micros)| (phase.to_string(), Duration::from_micros(micros)))
+            .collect();
+        HashMap::from([(1, HashMap::from([("default".to_string(), phases)]))])
+    }
+    fn regressed(comparisons: &[Comparison]) -> Vec<&str> {
+        let regressed = comparisons.iter().filter(|c| c.regressed);
+        regressed.map(|c| c.phase).collect()
+    }
+    #[test]
+    fn threshold() {
+        let runs = [run(Status::Match)];
+        let baseline = baseline(&[("parse", 50), ("part1", 95), ("part2", 89)]);
+        let comparisons = compare(&runs, &baseline, 10.0);
+        assert_eq!(comparisons.len(), 3);
+        assert_eq!(regressed(&comparisons), ["parse", "part2"]);
+        assert!((comparisons[0].change() - 100.0).abs() < 1e-9);
+        assert_eq!(
+            regressed(&compare(&runs, &baseline, 150.0)),
+            [] as [&str; 0]
+        );
+    }
+    #[test]
+    fn min_regression() {
+        let mut runs = [run(Status::Match)];
+        runs[0].parse_time = Duration::from_micros(9);
+        runs[0].parts[0].as_mut().unwrap().time = Duration::from_micros(30);
+        let baseline = baseline(&[("parse", 1), ("part1", 15)]);
+        // 9x slower, but by less than MIN_REGRESSION.
+        assert_eq!(regressed(&compare(&runs, &baseline, 10.0)), ["part1"]);
+    }
+    #[test]
+    fn missing_entries() {
+        let runs = [run(Status::Match)];
+        let comparisons = compare(&runs, &baseline(&[("part2", 1)]), 10.0);
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(comparisons[0].phase, "part2");
+        let mut other_input = run(Status::Match);
+        other_input.input = "alice".to_string();
+        assert!(compare(&[other_input], &baseline(&[("parse", 1)]), 10.0).is_empty());
+        assert!(compare(&runs, &Baseline::new(), 10.0).is_empty());
+    }
+    #[test]
+    fn failed_parts() {
+        let all = [("parse", 1), ("part1", 1), ("part2", 1)];
+        for status in [Status::Failed, Status::TimedOut] {
+            let comparisons = compare(&[run(status)], &baseline(&all), 10.0);
+            let phases: Vec<_> = comparisons.iter().map(|c| c.phase).collect();
+            assert_eq!(phases, ["parse", "part1"]);
+        }
+        let mut parse_error = run(Status::Failed);
+        parse_error.parts[0].as_mut().unwrap().status = Status::Failed;
+        assert!(compare(&[parse_error], &baseline(&all), 10.0).is_empty());
+
+        let file = std::env::temp_dir().join(format!("baseline_{}.json", std::process::id()));
+        save(&file, &[run(Status::TimedOut)]).unwrap();
+        let saved = load(&file).unwrap();
+        fs::remove_file(&file).unwrap();
+        let mut phases: Vec<_> = saved[&1]["default"].keys().collect();
+        phases.sort();
+        assert_eq!(phases, ["parse", "part1"]);
+    }
+}
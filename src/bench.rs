@@ -0,0 +1,116 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Minimum time spent warming up a phase before it is measured.
+const WARMUP_TIME: Duration = Duration::from_millis(100);
+/// Phases faster than this are run several times per sample, so the timer
+/// resolution and overhead don't dominate the measurement.
+const MIN_SAMPLE_TIME: Duration = Duration::from_micros(100);
+const MAX_BATCH: u32 = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: usize,
+    /// Number of runs timed together in each sample.
+    pub batch: u32,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>, batch: u32) -> Self {
+        assert!(!samples.is_empty());
+        samples.sort_unstable();
+        let len = samples.len();
+        let mean = samples.iter().sum::<Duration>() / len as u32;
+        let variance = samples
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / len as f64;
+        let median = if len.is_multiple_of(2) {
+            (samples[len / 2 - 1] + samples[len / 2]) / 2
+        } else {
+            samples[len / 2]
+        };
+        Self {
+            samples: len,
+            batch,
+            min: samples[0],
+            median,
+            mean,
+            p95: samples[(len * 95).div_ceil(100) - 1],
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// Times `routine` over `samples` samples after a warmup. `setup` creates the
+/// input for every run and is not included in the timings, and neither is
+/// dropping the outputs.
+pub fn measure<I, O>(
+    samples: usize,
+    mut setup: impl FnMut() -> I,
+    mut routine: impl FnMut(I) -> O,
+) -> Stats {
+    let mut warmup_runs = 0;
+    let mut warmup_time = Duration::ZERO;
+    while warmup_time < WARMUP_TIME {
+        let input = setup();
+        let start = Instant::now();
+        let output = black_box(routine(black_box(input)));
+        warmup_time += start.elapsed();
+        drop(output);
+        warmup_runs += 1;
+    }
+    let single_run = warmup_time / warmup_runs;
+    let batch = if single_run.is_zero() {
+        MAX_BATCH
+    } else {
+        (MIN_SAMPLE_TIME.as_nanos() / single_run.as_nanos())
+            .clamp(1, u128::from(MAX_BATCH))
+            .try_into()
+            .unwrap()
+    };
+
+    let times = (0..samples.max(1))
+        .map(|_| {
+            let inputs: Vec<I> = (0..batch).map(|_| setup()).collect();
+            let mut outputs = Vec::with_capacity(inputs.len());
+            let start = Instant::now();
+            for input in inputs {
+                outputs.push(black_box(routine(black_box(input))));
+            }
+            let elapsed = start.elapsed();
+            drop(outputs);
+            elapsed / batch
+        })
+        .collect();
+    Stats::from_samples(times, batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn stats() {
+        let samples = (1..=20).map(Duration::from_millis).collect();
+        let stats = Stats::from_samples(samples, 1);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(10_500));
+        assert_eq!(stats.mean, Duration::from_micros(10_500));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(stats.stddev.as_micros(), 5766);
+    }
+    #[test]
+    fn batches_fast_routines() {
+        let stats = measure(5, || 1, |x| x + 1);
+        assert_eq!(stats.samples, 5);
+        assert!(stats.batch > 1);
+    }
+}
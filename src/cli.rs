@@ -0,0 +1,375 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::{path::PathBuf, time::Duration};
+
+pub const USAGE: &str = "Usage: advent-2022 [OPTIONS] [DAYS]...
+
+Days can be given as single days, comma separated lists and ranges,
+for example `3,5,10-14`. All days are run when none are given.
+Exits with status 1 when an answer doesn't match its stored result,
+or when a phase regressed compared to the --baseline.
+A panicking part fails on its own, except in release builds, which
+abort: build with `--profile runner` for optimized builds that don't.
+Builds with `--features count-allocations` also report the heap
+allocations of every phase, and always run one day at a time.
+
+Options:
+  -p, --part <1|2>          Only run the given part
+  -i, --input <FILE>        Read the input from FILE instead, `-` reads stdin.
+                            Only valid when running a single day
+  -e, --example             Run the examples of the puzzle texts instead of the inputs,
+                            checking the answers they give, parts without one are skipped
+      --input-dir <DIR>     Directory to read <day>.txt inputs from [default: input]
+  -r, --results <FILE>      Stored results to compare against [default: results.json]
+  -j, --jobs <N>            Run up to N days at the same time [default: 1]
+      --serial-timing       With --jobs, never run the timed phases of two days
+                            at the same time, so the timings stay comparable
+      --bench <N>           Benchmark every phase over N samples after a warmup
+      --format <FORMAT>     Print a report as text, json, csv or junit [default: text].
+                            Other formats than text move the normal output to stderr
+      --save-baseline <FILE>
+                            Save the timings of this run as a baseline
+      --baseline <FILE>     Compare the timings of this run against a saved baseline,
+                            failing when a phase got slower than the threshold
+      --threshold <PERCENT> Allowed slowdown compared to the baseline [default: 10]
+      --record              Write new answers into the results file
+      --force               With --record, also overwrite stored answers that don't match
+      --variant <NAMES>     Run these comma separated solution variants instead of the
+                            default one, comparing their answers and timings.
+                            `all` runs every variant, --list shows them
+      --timeout <DURATION>  Fail parts that take longer than DURATION, like 30s, 500ms or 2m.
+                            Every part runs in a child process then, which parses
+                            the input and is killed when the time is up
+      --param <[DAY.]NAME=VALUE>
+                            Change a puzzle parameter, of every day that has it
+                            or only of DAY. Stored answers aren't checked then
+      --dump <DIR>          Write what solving an input looks like into DIR, for days
+                            that can show it, like day 10's trace or the animations
+                            of the gif variants. Happens once per input, untimed
+      --list                List the solutions of the given days instead of running them
+  -h, --help                Print this help";
+
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Options {
+    pub days: Vec<u8>,
+    pub parts: [bool; 2],
+    pub variants: Vec<String>,
+    pub params: Vec<ParamOverride>,
+    pub dump: Option<PathBuf>,
+    pub input: Option<PathBuf>,
+    pub example: bool,
+    pub timeout: Option<Duration>,
+    pub input_dir: PathBuf,
+    pub results: PathBuf,
+    pub record: bool,
+    pub force: bool,
+    pub jobs: usize,
+    pub serial_timing: bool,
+    pub bench: Option<usize>,
+    pub format: Format,
+    pub save_baseline: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    pub threshold: f64,
+}
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            days: vec![],
+            parts: [true, true],
+            variants: vec![],
+            params: vec![],
+            dump: None,
+            input: None,
+            example: false,
+            timeout: None,
+            input_dir: PathBuf::from("input"),
+            results: PathBuf::from("results.json"),
+            record: false,
+            force: false,
+            jobs: 1,
+            serial_timing: false,
+            bench: None,
+            format: Format::Text,
+            save_baseline: None,
+            baseline: None,
+            threshold: 10.0,
+        }
+    }
+}
+
+/// A puzzle parameter changed with `--param`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamOverride {
+    /// The only day to change it for, every day that has it when `None`.
+    pub day: Option<u8>,
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Junit,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run(Box<Options>),
+    /// List the solutions of these days.
+    List(Vec<u8>),
+    Help,
+}
+
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    let mut list = false;
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .with_context(|| format!("Missing value for {name}"))
+        };
+        match &flag[..] {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-p" | "--part" => {
+                options.parts = match &value(&flag)?[..] {
+                    "1" => [true, false],
+                    "2" => [false, true],
+                    other => bail!("Invalid part '{other}', expected 1 or 2"),
+                };
+            }
+            "--variant" => {
+                for variant in value(&flag)?.split(',').filter(|v| !v.is_empty()) {
+                    if !options.variants.iter().any(|v| v == variant) {
+                        options.variants.push(variant.to_string());
+                    }
+                }
+            }
+            "--param" => options.params.push(parse_param(&value(&flag)?)?),
+            "--dump" => options.dump = Some(PathBuf::from(value(&flag)?)),
+            "-i" | "--input" => options.input = Some(PathBuf::from(value(&flag)?)),
+            "-e" | "--example" => options.example = true,
+            "--input-dir" => options.input_dir = PathBuf::from(value(&flag)?),
+            "-r" | "--results" => options.results = PathBuf::from(value(&flag)?),
+            "-j" | "--jobs" => {
+                options.jobs = match value(&flag)?.parse() {
+                    Ok(jobs) if jobs > 0 => jobs,
+                    _ => bail!("Invalid number of jobs, expected a positive number"),
+                };
+            }
+            "--serial-timing" => options.serial_timing = true,
+            "--timeout" => options.timeout = Some(parse_duration(&value(&flag)?)?),
+            "--bench" => {
+                options.bench = match value(&flag)?.parse() {
+                    Ok(samples) if samples > 0 => Some(samples),
+                    _ => bail!("Invalid number of benchmark samples, expected a positive number"),
+                };
+            }
+            "--format" => {
+                options.format = match &value(&flag)?[..] {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    "junit" => Format::Junit,
+                    other => bail!("Invalid format '{other}', expected text, json, csv or junit"),
+                };
+            }
+            "--save-baseline" => options.save_baseline = Some(PathBuf::from(value(&flag)?)),
+            "--baseline" => options.baseline = Some(PathBuf::from(value(&flag)?)),
+            "--threshold" => {
+                options.threshold = match value(&flag)?.trim_end_matches('%').parse() {
+                    Ok(threshold) if threshold >= 0.0 => threshold,
+                    _ => bail!("Invalid threshold, expected a percentage like 10 or 2.5"),
+                };
+            }
+            "--list" => list = true,
+            "--record" => options.record = true,
+            "--force" => options.force = true,
+            _ if flag.starts_with('-') => bail!("Unknown option '{flag}'"),
+            _ => {
+                for day in parse_days(&arg)? {
+                    if !options.days.contains(&day) {
+                        options.days.push(day);
+                    }
+                }
+            }
+        }
+    }
+    if options.days.is_empty() {
+        options.days = (1..=25).collect();
+    }
+    if list {
+        return Ok(Command::List(options.days));
+    }
+    check(&options)?;
+    Ok(Command::Run(Box::new(options)))
+}
+
+/// Rejects options that don't go together.
+fn check(options: &Options) -> Result<()> {
+    if options.force && !options.record {
+        bail!("--force can only be used together with --record");
+    }
+    if options.example && options.input.is_some() {
+        bail!("--example can't be used together with --input");
+    }
+    if options.record && options.example {
+        bail!("--record can't be used together with --example");
+    }
+    if options.record && !options.params.is_empty() {
+        bail!("--record can't be used together with --param");
+    }
+    if options.timeout.is_some() && options.bench.is_some() {
+        bail!("--bench can't be used together with --timeout");
+    }
+    if options.timeout.is_some() && options.dump.is_some() {
+        bail!("--dump can't be used together with --timeout");
+    }
+    if options.input.is_some() && options.days.len() != 1 {
+        bail!("--input needs exactly one day to run");
+    }
+    Ok(())
+}
+
+fn parse_param(spec: &str) -> Result<ParamOverride> {
+    let Some((name, value)) = spec.split_once('=') else {
+        bail!("Invalid parameter '{spec}', expected NAME=VALUE or DAY.NAME=VALUE");
+    };
+    let (day, name) = match name.split_once('.') {
+        Some((day, name)) => (Some(parse_day(day)?), name),
+        None => (None, name),
+    };
+    if name.is_empty() {
+        bail!("Invalid parameter '{spec}', the name is missing");
+    }
+    Ok(ParamOverride {
+        day,
+        name: name.to_string(),
+        value: value.to_string(),
+    })
+}
+
+fn parse_duration(text: &str) -> Result<Duration> {
+    let unit = text.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+    let seconds = match unit {
+        "ms" => 0.001,
+        "s" | "" => 1.0,
+        "m" => 60.0,
+        _ => bail!("Invalid duration unit '{unit}', expected ms, s or m"),
+    };
+    match text[..text.len() - unit.len()].parse::<f64>() {
+        Ok(number) if number > 0.0 && number.is_finite() => {
+            Duration::try_from_secs_f64(number * seconds)
+                .map_err(|e| anyhow!("Invalid duration '{text}', {e}"))
+        }
+        _ => bail!("Invalid duration '{text}', expected a positive number like 30s"),
+    }
+}
+
+fn parse_day(day: &str) -> Result<u8> {
+    match day.trim().parse() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => bail!("Invalid day '{day}', expected a number from 1 to 25"),
+    }
+}
+
+pub fn parse_days(spec: &str) -> Result<Vec<u8>> {
+    let mut days = vec![];
+    for item in spec.split(',').filter(|item| !item.is_empty()) {
+        if let Some((from, to)) = item.split_once('-') {
+            let (from, to) = (parse_day(from)?, parse_day(to)?);
+            if from > to {
+                bail!("Invalid day range '{item}', {from} is after {to}");
+            }
+            days.extend(from..=to);
+        } else {
+            days.push(parse_day(item)?);
+        }
+    }
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn args(args: &str) -> Result<Command> {
+        parse(args.split_whitespace().map(str::to_string))
+    }
+    #[test]
+    fn day_lists() {
+        assert_eq!(parse_days("3,5,10-14").unwrap(), [3, 5, 10, 11, 12, 13, 14]);
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("5-3").is_err());
+        assert!(parse_days("x").is_err());
+    }
+    #[test]
+    fn options() {
+        let Command::Run(options) = args("4 -p 2 2-4 --results=other.json").unwrap() else {
+            panic!("Expected run command");
+        };
+        assert_eq!(options.days, [4, 2, 3]);
+        assert_eq!(options.parts, [false, true]);
+        assert_eq!(options.results, PathBuf::from("other.json"));
+        let Command::Run(options) = args("--variant default,gif --variant gif").unwrap() else {
+            panic!("Expected run command");
+        };
+        assert_eq!(options.variants, ["default", "gif"]);
+        let Command::Run(options) = args("--param row=10 --param=15.max=20").unwrap() else {
+            panic!("Expected run command");
+        };
+        let param = |day, name: &str, value: &str| ParamOverride {
+            day,
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        assert_eq!(
+            options.params,
+            [param(None, "row", "10"), param(Some(15), "max", "20")]
+        );
+        assert!(args("--param row").is_err());
+        assert!(args("--param 26.row=1").is_err());
+        assert!(args("--param =1").is_err());
+        assert!(args("--record --param row=1").is_err());
+        assert!(args("--example 3").is_ok());
+        assert!(args("--example --input - 3").is_err());
+        assert!(args("--example --record").is_err());
+        assert_eq!(args("--help").unwrap(), Command::Help);
+        assert_eq!(args("--list 3-4").unwrap(), Command::List(vec![3, 4]));
+        assert!(args("--part 3").is_err());
+        assert!(args("--part").is_err());
+        assert!(args("--frobnicate").is_err());
+        assert!(args("--input - 1-2").is_err());
+        assert!(args("--input -").is_err());
+        assert!(args("--input - 3").is_ok());
+        assert!(args("--force").is_err());
+        assert!(args("--record --force").is_ok());
+        assert!(args("--bench 0").is_err());
+        assert!(args("--bench x").is_err());
+        assert!(args("--timeout 1s --bench 5").is_err());
+        assert!(args("--timeout 1s --dump out").is_err());
+        assert!(args("--jobs 0").is_err());
+        assert!(args("-j 4 --serial-timing").is_ok());
+        assert!(args("--format xml").is_err());
+        assert!(args("--threshold -5").is_err());
+        assert!(args("--threshold 12.5%").is_ok());
+        let timeout = |arg| parse_duration(arg).ok();
+        assert_eq!(timeout("1.5"), Some(Duration::from_millis(1500)));
+        assert_eq!(timeout("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(timeout("2m"), Some(Duration::from_secs(120)));
+        assert_eq!(timeout("0s"), None);
+        assert_eq!(timeout("5h"), None);
+        assert_eq!(timeout("ms"), None);
+        assert_eq!(timeout("99999999999999999999999"), None);
+    }
+}
@@ -1,8 +1,141 @@
-use anyhow::Result;
+use crate::answer::Answer;
+use anyhow::{bail, Result};
+use std::{fmt::Debug, io::Write};
+
 pub trait Day {
+    type Parsed;
+    type Output: Into<Answer>;
+    /// Puzzle parameters, `()` for days without any.
+    type Params: Params;
+    /// Set when [`Day::both`] shares work between the parts, so the runner
+    /// solves them in one go whenever both are asked for.
+    const SOLVES_BOTH: bool = false;
+    /// The examples of the puzzle text, for `--example`.
+    const EXAMPLES: &'static [Example] = &[];
+    fn parse(input: String) -> Result<Self::Parsed>;
+    fn first(data: &Self::Parsed, params: &Self::Params) -> Result<Self::Output>;
+    fn second(data: &Self::Parsed, params: &Self::Params) -> Result<Self::Output>;
+    /// The answers of both parts. Only worth overriding along with
+    /// [`Day::SOLVES_BOTH`].
+    fn both(data: &Self::Parsed, params: &Self::Params) -> Result<(Self::Output, Self::Output)> {
+        Ok((Self::first(data, params)?, Self::second(data, params)?))
+    }
+    /// Extension of the file [`Day::dump`] writes, for days that can show
+    /// more of solving an input than the answers.
+    const DUMP: Option<&'static str> = None;
+    /// Writes what solving `data` looks like, like a trace or an animation,
+    /// for `--dump`. Only called for days with a [`Day::DUMP`] extension, and
+    /// never while timing.
+    fn dump(_data: &Self::Parsed, _params: &Self::Params, _out: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A day whose parts take the parsed input by value, because they change it
+/// as they go. Every part gets a copy of its own, which is timed separately.
+pub trait ConsumingDay {
     type Parsed: Clone;
-    type Output: ToString;
+    type Output: Into<Answer>;
+    /// See [`Day::Params`].
+    type Params: Params;
+    /// See [`Day::SOLVES_BOTH`].
+    const SOLVES_BOTH: bool = false;
+    /// See [`Day::EXAMPLES`].
+    const EXAMPLES: &'static [Example] = &[];
     fn parse(input: String) -> Result<Self::Parsed>;
-    fn first(data: Self::Parsed) -> Self::Output;
-    fn second(data: Self::Parsed) -> Self::Output;
+    fn first(data: Self::Parsed, params: &Self::Params) -> Result<Self::Output>;
+    fn second(data: Self::Parsed, params: &Self::Params) -> Result<Self::Output>;
+    /// See [`Day::both`].
+    fn both(data: Self::Parsed, params: &Self::Params) -> Result<(Self::Output, Self::Output)> {
+        Ok((
+            Self::first(data.clone(), params)?,
+            Self::second(data, params)?,
+        ))
+    }
+    /// See [`Day::DUMP`].
+    const DUMP: Option<&'static str> = None;
+    /// See [`Day::dump`], the input is only borrowed here.
+    fn dump(_data: &Self::Parsed, _params: &Self::Params, _out: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// An example input of the puzzle text, with the answers it gives.
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    /// Name of the input, `example` unless a day has several.
+    pub name: &'static str,
+    pub input: &'static str,
+    /// Answers of both parts, `None` when the puzzle text has no answer.
+    pub answers: [Option<&'static str>; 2],
+    /// Puzzle parameters the example needs, by name.
+    pub params: &'static [(&'static str, &'static str)],
+}
+impl Example {
+    pub const fn new(input: &'static str, answers: [Option<&'static str>; 2]) -> Self {
+        Self {
+            name: "example",
+            input,
+            answers,
+            params: &[],
+        }
+    }
+}
+
+/// Numbers a puzzle is about besides its input, like how many rounds to
+/// play. The defaults are those of the real puzzle, and they can be changed
+/// by name for other inputs like the examples. Declared with [`params!`].
+pub trait Params: Default + Debug + 'static {
+    /// Changes the parameter `name` to `value`.
+    fn set(&mut self, name: &str, value: &str) -> Result<()>;
+    /// Every parameter by name, with its value.
+    fn values(&self) -> Vec<(&'static str, String)>;
+}
+impl Params for () {
+    fn set(&mut self, name: &str, _value: &str) -> Result<()> {
+        bail!("No parameter named {name:?}, this day has none")
+    }
+    fn values(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+}
+
+/// Declares a [`Params`] struct, with the default value of every field.
+macro_rules! params {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $($(#[$field_meta:meta])* $field:ident: $type:ty = $default:expr,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name {
+            $($(#[$field_meta])* pub $field: $type,)*
+        }
+        impl Default for $name {
+            fn default() -> Self {
+                Self {
+                    $($field: $default,)*
+                }
+            }
+        }
+        impl $crate::days::day::Params for $name {
+            fn set(&mut self, name: &str, value: &str) -> anyhow::Result<()> {
+                match name {
+                    $(stringify!($field) => {
+                        self.$field = anyhow::Context::with_context(value.parse(), || {
+                            format!("Invalid value {value:?} for parameter {name:?}")
+                        })?;
+                    })*
+                    _ => anyhow::bail!("No parameter named {name:?}"),
+                }
+                Ok(())
+            }
+            fn values(&self) -> Vec<(&'static str, String)> {
+                vec![$((stringify!($field), self.$field.to_string()),)*]
+            }
+        }
+    };
 }
+pub(crate) use params;
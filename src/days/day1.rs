@@ -1,66 +1,72 @@
-use super::day::Day;
-use anyhow::Result;
+use super::day::{Day, Example};
+use crate::helpers::parse::lines;
+use anyhow::{Context, Result};
 use itertools::Itertools;
 
+const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
 pub struct Day1;
 impl Day for Day1 {
     type Parsed = Vec<Vec<i32>>;
     type Output = i32;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("24000"), Some("45000")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input.lines().fold(vec![vec![]], |mut elves, line| {
-            match line {
-                "" => elves.push(vec![]),
-                _ => elves.last_mut().unwrap().push(line.parse::<i32>().unwrap()),
+        let mut elves = vec![vec![]];
+        for mut line in lines(&input) {
+            if line.is_empty() {
+                elves.push(vec![]);
+            } else {
+                elves.last_mut().unwrap().push(line.number()?);
+                line.end()?;
             }
-            elves
-        }))
+        }
+        Ok(elves)
     }
-    fn first(elves: Self::Parsed) -> Self::Output {
+    fn first(elves: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         elves
-            .into_iter()
+            .iter()
             .map(|e| e.iter().sum::<i32>())
-            .sorted()
-            .last()
-            .unwrap()
+            .max()
+            .context("No elves")
     }
-    fn second(elves: Self::Parsed) -> Self::Output {
-        elves
-            .into_iter()
+    fn second(elves: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(elves
+            .iter()
             .map(|e| e.iter().sum::<i32>())
             .sorted()
             .rev()
             .take(3)
-            .sum()
+            .sum())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "1000
-2000
-3000
-
-4000
-
-5000
-6000
-
-7000
-8000
-9000
-
-10000";
     fn parsed() -> <Day1 as Day>::Parsed {
-        Day1::parse(INPUT.to_string()).unwrap()
+        Day1::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day1::first(parsed()), 24000);
+        assert_eq!(Day1::first(&parsed(), &()).unwrap(), 24000);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day1::second(parsed()), 45000);
+        assert_eq!(Day1::second(&parsed(), &()).unwrap(), 45000);
     }
 }
@@ -1,91 +1,209 @@
-use super::day::Day;
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+use super::day::{Day, Example};
+use crate::answer::Answer;
+use crate::helpers::{
+    ocr,
+    parse::{lines, ParseError},
+};
 use anyhow::Result;
+use itertools::Itertools;
+use smallvec::SmallVec;
+use std::{
+    fmt::{self, Write},
+    io,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Command {
-    Noop,
-    Addx(i32),
-}
-impl Command {
-    fn run(self, x: &mut i32) {
-        match self {
-            Self::Noop => {}
-            Self::Addx(constant) => *x += constant,
-        }
+pub struct Registers {
+    pub x: i32,
+}
+impl Default for Registers {
+    fn default() -> Self {
+        Self { x: 1 }
+    }
+}
+
+/// An instruction the CPU knows: how many number operands it takes, how many
+/// cycles it runs for, and how it changes the registers when it's done.
+#[derive(Debug, Clone, Copy)]
+pub struct Opcode {
+    pub name: &'static str,
+    pub operands: usize,
+    pub cycles: usize,
+    pub execute: fn(&mut Registers, &[i32]),
+}
+
+pub const NOOP: Opcode = Opcode {
+    name: "noop",
+    operands: 0,
+    cycles: 1,
+    execute: |_, _| {},
+};
+pub const ADDX: Opcode = Opcode {
+    name: "addx",
+    operands: 1,
+    cycles: 2,
+    execute: |registers, operands| registers.x += operands[0],
+};
+
+/// The opcodes a program can use, [`NOOP`] and [`ADDX`] by default.
+#[derive(Debug, Clone)]
+pub struct InstructionSet {
+    opcodes: Vec<Opcode>,
+}
+impl Default for InstructionSet {
+    fn default() -> Self {
+        let mut instructions = Self { opcodes: vec![] };
+        instructions.register(NOOP).register(ADDX);
+        instructions
     }
-    const fn time(self) -> i32 {
-        match self {
-            Self::Noop => 1,
-            Self::Addx(_) => 2,
+}
+impl InstructionSet {
+    /// Adds an opcode, replacing the one with the same name.
+    pub fn register(&mut self, opcode: Opcode) -> &mut Self {
+        assert!(opcode.cycles > 0, "Opcode {} takes no time", opcode.name);
+        self.opcodes.retain(|known| known.name != opcode.name);
+        self.opcodes.push(opcode);
+        self
+    }
+    /// Parses a program of one instruction per line, like `addx -3`.
+    pub fn parse(&self, input: &str) -> Result<Vec<Instruction>, ParseError> {
+        lines(input)
+            .map(|mut line| {
+                let start = line;
+                let opcode = line
+                    .word()
+                    .ok()
+                    .and_then(|name| self.opcodes.iter().find(|opcode| opcode.name == name))
+                    .ok_or_else(|| {
+                        start.error(
+                            self.opcodes
+                                .iter()
+                                .map(|opcode| format!("{:?}", opcode.name))
+                                .join(" or "),
+                        )
+                    })?;
+                let operands = (0..opcode.operands)
+                    .map(|_| {
+                        line.literal(" ")?;
+                        line.number()
+                    })
+                    .collect::<Result<_, _>>()?;
+                line.end()?;
+                Ok(Instruction {
+                    opcode: *opcode,
+                    operands,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub operands: SmallVec<[i32; 2]>,
+}
+/// The instruction as it was written in the program.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.opcode.name)?;
+        for operand in &self.operands {
+            write!(f, " {operand}")?;
         }
+        Ok(())
     }
 }
 
-pub struct Day10;
-impl Day for Day10 {
-    type Parsed = Vec<Command>;
-    type Output = String;
+/// What the CPU does during a cycle, with the registers as they are before
+/// an instruction finishing in it changes them.
+#[derive(Debug, Clone)]
+pub struct Cycle<'a> {
+    /// Starting at 1.
+    pub number: usize,
+    pub registers: Registers,
+    /// Index of the running instruction in the program.
+    pub pc: usize,
+    pub instruction: &'a Instruction,
+    /// How many cycles the instruction has run, including this one.
+    pub progress: usize,
+}
+/// A line of the trace, like `   3 | x=1    |   1: addx 3 (1/2)`.
+impl fmt::Display for Cycle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>4} | x={:<4} | {:>3}: {} ({}/{})",
+            self.number,
+            self.registers.x,
+            self.pc,
+            self.instruction,
+            self.progress,
+            self.instruction.opcode.cycles
+        )
+    }
+}
 
-    fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .lines()
-            .map(|line| {
-                let mut split = line.split(' ');
-                match split.next().unwrap() {
-                    "noop" => Command::Noop,
-                    "addx" => Command::Addx(split.next().unwrap().parse().unwrap()),
-                    _ => panic!(),
-                }
-            })
-            .collect())
+pub struct Cpu<'a> {
+    program: &'a [Instruction],
+    pub registers: Registers,
+    pc: usize,
+    /// Cycles the running instruction has run.
+    busy: usize,
+    cycles: usize,
+}
+impl<'a> Cpu<'a> {
+    pub fn new(program: &'a [Instruction]) -> Self {
+        Self {
+            program,
+            registers: Registers::default(),
+            pc: 0,
+            busy: 0,
+            cycles: 0,
+        }
     }
-    fn first(code: Self::Parsed) -> Self::Output {
-        let mut x = 1;
-        let mut cycles = 0;
-        let mut next_check = 20;
-        let mut sum_report = 0;
-        for line in code {
-            let future_time = cycles + line.time();
-            if next_check < future_time + 1 {
-                sum_report += next_check * x;
-                next_check += 40;
-            }
-            line.run(&mut x);
-            if next_check <= future_time {
-                sum_report += next_check * x;
-                next_check += 40;
-            }
-            cycles = future_time;
+    /// Runs a single cycle, `None` once the program has ended.
+    pub fn step(&mut self) -> Option<Cycle<'a>> {
+        let instruction = self.program.get(self.pc)?;
+        self.cycles += 1;
+        self.busy += 1;
+        let cycle = Cycle {
+            number: self.cycles,
+            registers: self.registers,
+            pc: self.pc,
+            instruction,
+            progress: self.busy,
+        };
+        if self.busy == instruction.opcode.cycles {
+            (instruction.opcode.execute)(&mut self.registers, &instruction.operands);
+            self.pc += 1;
+            self.busy = 0;
         }
-        sum_report.to_string()
+        Some(cycle)
     }
-    fn second(code: Self::Parsed) -> Self::Output {
-        let mut x = 1;
-        let mut report = String::new();
-        let mut cycles = 0;
-        for line in code {
-            for cycles_plus in 0..line.time() {
-                let x_pos = (cycles + cycles_plus) % 40;
-                if x_pos == 0 && cycles != 0 {
-                    report.push('\n');
-                }
-                if x_pos >= x - 1 && x_pos <= x + 1 {
-                    report.push('#');
-                } else {
-                    report.push('.');
-                }
-            }
-            cycles += line.time();
-            line.run(&mut x);
+    /// Runs the program to its end, calling `observer` during every cycle.
+    pub fn run(&mut self, mut observer: impl FnMut(&Cycle)) {
+        while let Some(cycle) = self.step() {
+            observer(&cycle);
         }
-        report
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const INPUT: &str = "addx 15
+fn disassemble(program: &[Instruction]) -> String {
+    let mut listing = String::new();
+    for (pc, instruction) in program.iter().enumerate() {
+        writeln!(listing, "{pc:>3}: {instruction}").unwrap();
+    }
+    listing
+}
+
+fn trace(program: &[Instruction]) -> String {
+    let mut trace = String::new();
+    Cpu::new(program).run(|cycle| writeln!(trace, "{cycle}").unwrap());
+    trace
+}
+
+const EXAMPLE: &str = "addx 15
 addx -11
 addx 6
 addx -3
@@ -231,23 +349,107 @@ addx -11
 noop
 noop
 noop";
+
+/// What the example draws in part 2.
+const EXAMPLE_SCREEN: &str = "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....";
+
+pub struct Day10;
+impl Day for Day10 {
+    type Parsed = Vec<Instruction>;
+    type Output = Answer;
+    type Params = ();
+    const EXAMPLES: &'static [Example] =
+        &[Example::new(EXAMPLE, [Some("13140"), Some(EXAMPLE_SCREEN)])];
+
+    fn parse(input: String) -> Result<Self::Parsed> {
+        Ok(InstructionSet::default().parse(&input)?)
+    }
+    fn first(code: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        let mut sum_report = 0;
+        Cpu::new(code).run(|cycle| {
+            if cycle.number % 40 == 20 {
+                sum_report += cycle.number as i32 * cycle.registers.x;
+            }
+        });
+        Ok(sum_report.into())
+    }
+    fn second(code: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        let mut report = String::new();
+        Cpu::new(code).run(|cycle| {
+            let x_pos = (cycle.number - 1) % 40;
+            if x_pos == 0 && cycle.number != 1 {
+                report.push('\n');
+            }
+            if (x_pos as i32 - cycle.registers.x).abs() <= 1 {
+                report.push('#');
+            } else {
+                report.push('.');
+            }
+        });
+        let rows: Vec<_> = report.lines().collect();
+        Ok(ocr::read_letters(&rows).map_or_else(|| Answer::grid(&report), Answer::Text))
+    }
+    const DUMP: Option<&'static str> = Some("txt");
+    /// The listing of the program, then what it does every cycle.
+    fn dump(code: &Self::Parsed, _: &Self::Params, out: &mut dyn io::Write) -> Result<()> {
+        write!(out, "{}\n{}", disassemble(code), trace(code))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
     fn parsed() -> <Day10 as Day>::Parsed {
-        Day10::parse(INPUT.to_string()).unwrap()
+        Day10::parse(EXAMPLE.to_string()).unwrap()
+    }
+    #[test]
+    fn cpu() {
+        let mut instructions = InstructionSet::default();
+        instructions.register(Opcode {
+            name: "mulx",
+            operands: 1,
+            cycles: 3,
+            execute: |registers, operands| registers.x *= operands[0],
+        });
+        let program = instructions.parse("noop\naddx 3\nmulx -2").unwrap();
+        assert_eq!(
+            disassemble(&program),
+            "  0: noop\n  1: addx 3\n  2: mulx -2\n"
+        );
+        let mut cpu = Cpu::new(&program);
+        let cycles: Vec<_> = std::iter::from_fn(|| cpu.step().map(|c| c.registers.x)).collect();
+        assert_eq!(cycles, [1, 1, 1, 4, 4, 4]);
+        assert_eq!(cpu.registers.x, -8);
+        assert_eq!(
+            trace(&program).lines().nth(2),
+            Some("   3 | x=1    |   1: addx 3 (2/2)")
+        );
+        let error = InstructionSet::default().parse("mulx 2").unwrap_err();
+        assert_eq!(error.expected, "\"noop\" or \"addx\"");
     }
     #[test]
     fn part1() {
-        assert_eq!(Day10::first(parsed()), "13140");
+        assert_eq!(Day10::first(&parsed(), &()).unwrap(), Answer::Number(13140));
     }
     #[test]
     fn part2() {
         assert_eq!(
-            Day10::second(parsed()),
-            "##..##..##..##..##..##..##..##..##..##..
-###...###...###...###...###...###...###.
-####....####....####....####....####....
-#####.....#####.....#####.....#####.....
-######......######......######......####
-#######.......#######.......#######....."
+            Day10::second(&parsed(), &()).unwrap(),
+            Answer::grid(EXAMPLE_SCREEN)
         );
     }
+    #[test]
+    fn dump() {
+        let mut out = vec![];
+        Day10::dump(&parsed(), &(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("  0: addx 15\n  1: addx -11\n"));
+        assert!(out.contains("\n\n   1 | x=1    |   0: addx 15 (1/2)\n"));
+    }
 }
@@ -1,4 +1,5 @@
-use super::day::Day;
+use super::day::{ConsumingDay, Example};
+use crate::helpers::parse::{lines, Line};
 use anyhow::Result;
 use itertools::Itertools;
 use rustc_hash::FxHashMap;
@@ -36,30 +37,90 @@ pub struct Monkey {
     throws: i64,
 }
 
+const EXAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
 pub struct Day11;
-impl Day for Day11 {
+impl ConsumingDay for Day11 {
     type Parsed = Vec<Monkey>;
     type Output = i64;
+    type Params = ();
+    const EXAMPLES: &'static [Example] =
+        &[Example::new(EXAMPLE, [Some("10605"), Some("2713310158")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        let mut it = input.lines();
+        let mut input = lines(&input);
         let mut monkeys = vec![];
-        while let Some(_monkey) = it.next() {
-            let items: VecDeque<i64> = it.next().unwrap()[18..]
-                .split(", ")
-                .map(|s| s.parse().unwrap())
-                .collect();
-            let op = &it.next().unwrap()[23..];
-            let val = op[2..].parse().ok();
-            let operation = match &op[0..1] {
-                "*" => Operation::Multiplication(val),
-                "+" => Operation::Addition(val),
-                _ => panic!(),
+        // Lines at the monkeys thrown to, checked once every monkey is known.
+        let mut targets = vec![];
+        while let Some(mut line) = input.next() {
+            line.literal("Monkey ")?;
+            line.number_in(monkeys.len()..=monkeys.len())?;
+            line.literal(":")?;
+            line.end()?;
+
+            let mut line = input.expect_line("the starting items")?;
+            line.literal("  Starting items: ")?;
+            let items: VecDeque<i64> = line.separated(", ", Line::number)?.into();
+
+            let mut line = input.expect_line("the operation")?;
+            line.literal("  Operation: new = old ")?;
+            let multiply = line.one_of(&[("* ", true), ("+ ", false)])?;
+            let val = if line.rest() == "old" {
+                line.literal("old")?;
+                None
+            } else {
+                Some(line.number()?)
+            };
+            line.end()?;
+            let operation = if multiply {
+                Operation::Multiplication(val)
+            } else {
+                Operation::Addition(val)
             };
-            let test = it.next().unwrap()[21..].parse()?;
-            let if_true = it.next().unwrap()[29..].parse()?;
-            let if_false = it.next().unwrap()[30..].parse()?;
-            it.next();
+
+            let mut line = input.expect_line("the test")?;
+            line.literal("  Test: divisible by ")?;
+            let test = line.number_in(1..=i64::MAX)?;
+            line.end()?;
+            let mut line = input.expect_line("the monkey to throw to if true")?;
+            line.literal("    If true: throw to monkey ")?;
+            targets.push(line);
+            let if_true = line.number()?;
+            line.end()?;
+            let mut line = input.expect_line("the monkey to throw to if false")?;
+            line.literal("    If false: throw to monkey ")?;
+            targets.push(line);
+            let if_false = line.number()?;
+            line.end()?;
+            if let Some(line) = input.next() {
+                line.end()?;
+            }
             monkeys.push(Monkey {
                 items,
                 operation,
@@ -69,9 +130,12 @@ impl Day for Day11 {
                 throws: 0,
             });
         }
+        for mut target in targets {
+            target.number_in(0..=monkeys.len() - 1)?;
+        }
         Ok(monkeys)
     }
-    fn first(mut monkeys: Self::Parsed) -> Self::Output {
+    fn first(mut monkeys: Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         for _ in 1..=20 {
             for i in 0..monkeys.len() {
                 while let Some(mut item_worry) = monkeys[i].items.pop_front() {
@@ -86,15 +150,15 @@ impl Day for Day11 {
                 }
             }
         }
-        monkeys
+        Ok(monkeys
             .iter()
             .map(|m| m.throws)
             .sorted()
             .rev()
             .take(2)
-            .product()
+            .product())
     }
-    fn second(mut monkeys: Self::Parsed) -> Self::Output {
+    fn second(mut monkeys: Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         // LCM of list of unique primes = product of list
         let lcm: i64 = monkeys.iter().map(|m| m.div_test).product();
         let mut monkey_states: FxHashMap<_, (_, Vec<_>)> = FxHashMap::default();
@@ -138,55 +202,44 @@ impl Day for Day11 {
             }
             x += 1;
         }
-        monkeys
+        Ok(monkeys
             .iter()
             .map(|m| m.throws)
             .sorted()
             .rev()
             .take(2)
-            .product()
+            .product())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "Monkey 0:
-  Starting items: 79, 98
-  Operation: new = old * 19
-  Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
-
-Monkey 1:
-  Starting items: 54, 65, 75, 74
-  Operation: new = old + 6
-  Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
-
-Monkey 2:
-  Starting items: 79, 60, 97
-  Operation: new = old * old
-  Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
-
-Monkey 3:
-  Starting items: 74
-  Operation: new = old + 3
-  Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1";
-    fn parsed() -> <Day11 as Day>::Parsed {
-        Day11::parse(INPUT.to_string()).unwrap()
+    use crate::helpers::parse::ParseError;
+    fn parsed() -> <Day11 as ConsumingDay>::Parsed {
+        Day11::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day11::first(parsed()), 10605);
+        assert_eq!(Day11::first(parsed(), &()).unwrap(), 10605);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day11::second(parsed()), 2713310158);
+        assert_eq!(Day11::second(parsed(), &()).unwrap(), 2_713_310_158);
+    }
+    #[test]
+    fn parse_errors() {
+        let diagram = |input: String| {
+            let error = Day11::parse(input).unwrap_err();
+            error.downcast::<ParseError>().unwrap().diagram()
+        };
+        assert_eq!(
+            diagram(EXAMPLE.replacen("monkey 3", "monkey 4", 1)),
+            "  |\n6 |     If false: throw to monkey 4\n  |                               ^ expected a number from 0 to 3"
+        );
+        assert_eq!(
+            diagram(EXAMPLE.replace("by 23", "by 0")),
+            "  |\n4 |   Test: divisible by 0\n  |                      ^ expected a number from 1 to 9223372036854775807"
+        );
     }
 }
@@ -1,10 +1,11 @@
-use super::day::Day;
-use anyhow::Result;
+use super::day::{Day, Example};
+use crate::helpers::parse::{lines, ParseError};
+use anyhow::{Context, Result};
 use pathfinding::prelude::bfs;
 // use pathfinding::prelude::dijkstra;
 use smallvec::{smallvec, SmallVec};
 
-fn explore_to_top(map: &[Vec<u32>], start: Position, top: Position) -> usize {
+fn explore_to_top(map: &[Vec<u32>], start: Position, top: Position) -> Result<usize> {
     let max = (map.len() - 1, map[1].len() - 1);
     let result = bfs(
         &(start.y, start.x),
@@ -26,9 +27,9 @@ fn explore_to_top(map: &[Vec<u32>], start: Position, top: Position) -> usize {
         },
         |&p| p == (top.y, top.x),
     );
-    result.unwrap().len() - 1
+    Ok(result.context("No path from the start to the top")?.len() - 1)
 }
-/* fn explore_to_top_dijkstra(map: &[Vec<u32>], start: Position, top: Position) -> usize {
+/* fn explore_to_top_dijkstra(map: &[Vec<u32>], start: Position, top: Position) -> Result<usize> {
     let max = (map.len() - 1, map[1].len() - 1);
     let result = dijkstra(
         &(start.y, start.x),
@@ -50,9 +51,9 @@ fn explore_to_top(map: &[Vec<u32>], start: Position, top: Position) -> usize {
         },
         |&p| p == (top.y, top.x),
     );
-    result.unwrap().1 as usize
+    Ok(result.context("No path from the start to the top")?.1 as usize)
 } */
-fn explore_from_top(map: &[Vec<u32>], top: Position) -> usize {
+fn explore_from_top(map: &[Vec<u32>], top: Position) -> Result<usize> {
     let max = (map.len() - 1, map[1].len() - 1);
     let result = bfs(
         &(top.y, top.x),
@@ -74,9 +75,12 @@ fn explore_from_top(map: &[Vec<u32>], top: Position) -> usize {
         },
         |&p| map[p.0][p.1] == 0,
     );
-    result.unwrap().len() - 1
+    Ok(result
+        .context("No path from the top down to the lowest square")?
+        .len()
+        - 1)
 }
-/* fn explore_from_top_djikstra(map: &[Vec<u32>], top: Position) -> usize {
+/* fn explore_from_top_djikstra(map: &[Vec<u32>], top: Position) -> Result<usize> {
     let max = (map.len() - 1, map[1].len() - 1);
     let result = dijkstra(
         &(top.y, top.x),
@@ -98,7 +102,7 @@ fn explore_from_top(map: &[Vec<u32>], top: Position) -> usize {
         },
         |&p| map[p.0][p.1] == 0,
     );
-    result.unwrap().1 as usize
+    Ok(result.context("No path from the top down to the lowest square")?.1 as usize)
 } */
 
 /* fn print(map: &[Vec<u32>], res: &Vec<(usize, usize)>) {
@@ -131,62 +135,69 @@ pub struct Position {
     y: usize,
 }
 
+const EXAMPLE: &str = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
 pub struct Day12;
 impl Day for Day12 {
     type Parsed = (Vec<Vec<u32>>, Position, Position);
     type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("31"), Some("29")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        let mut start = Position { x: 0, y: 0 };
-        let mut end = start;
-        let v = input
-            .lines()
-            .enumerate()
-            .map(|(y, line)| {
-                line.chars()
+        let (mut start, mut end) = (None, None);
+        let v = lines(&input)
+            .map(|line| {
+                line.check_chars(r#"a height, "S" or "E""#, |c| {
+                    c.is_ascii_lowercase() || c == 'S' || c == 'E'
+                })?;
+                let y = line.number - 1;
+                Ok(line
+                    .text()
+                    .chars()
                     .enumerate()
                     .map(|(x, c)| match c {
-                        'a'..='z' => c as u32 - 'a' as u32,
                         'S' => {
-                            start = Position { x, y };
+                            start = Some(Position { x, y });
                             0
                         }
                         'E' => {
-                            end = Position { x, y };
+                            end = Some(Position { x, y });
                             25
                         }
-                        _ => panic!(),
+                        _ => c as u32 - 'a' as u32,
                     })
-                    .collect()
+                    .collect())
             })
-            .collect();
+            .collect::<Result<_, ParseError>>()?;
+        let start = start.context(r#"No start position "S" in the map"#)?;
+        let end = end.context(r#"No best signal position "E" in the map"#)?;
         Ok((v, start, end))
     }
-    fn first((map, start, end): Self::Parsed) -> Self::Output {
-        explore_to_top(&map, start, end)
+    fn first((map, start, end): &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        explore_to_top(map, *start, *end)
     }
-    fn second((map, _start, end): Self::Parsed) -> Self::Output {
-        explore_from_top(&map, end)
+    fn second((map, _start, end): &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        explore_from_top(map, *end)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "Sabqponm
-abcryxxl
-accszExk
-acctuvwj
-abdefghi";
     fn parsed() -> <Day12 as Day>::Parsed {
-        Day12::parse(INPUT.to_string()).unwrap()
+        Day12::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day12::first(parsed()), 31);
+        assert_eq!(Day12::first(&parsed(), &()).unwrap(), 31);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day12::second(parsed()), 29);
+        assert_eq!(Day12::second(&parsed(), &()).unwrap(), 29);
     }
 }
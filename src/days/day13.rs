@@ -1,4 +1,5 @@
-use super::day::Day;
+use super::day::{ConsumingDay, Example};
+use crate::helpers::parse::{lines, Line, ParseError};
 use anyhow::Result;
 use itertools::Itertools;
 use std::cmp::Ordering;
@@ -53,56 +54,68 @@ impl PartialOrd for Packet {
     }
 }
 
-fn recurse_parse(input: &mut &str) -> Packet {
+fn parse_packet(line: &mut Line) -> Result<Packet, ParseError> {
     let mut packet = Packet::new();
+    line.literal("[")?;
+    if line.rest().starts_with(']') {
+        line.literal("]")?;
+        return Ok(packet);
+    }
     loop {
-        let mut it = input.chars();
-        match it.next() {
-            Some('[') => {
-                *input = &input[1..];
-                packet.push(recurse_parse(input));
-            }
-            Some(']') => {
-                *input = &input[1..];
-                return packet;
-            }
-            Some(first_num) if first_num >= '0' && first_num <= '9' => match it.next() {
-                Some(second_num) if second_num >= '0' && second_num <= '9' => {
-                    packet.push_num(input[0..2].parse().unwrap());
-                    *input = &input[2..];
-                }
-                _ => {
-                    *input = &input[1..];
-                    packet.push_num(first_num.to_digit(10).unwrap().try_into().unwrap());
-                }
-            },
-            Some(_) => {
-                *input = &input[1..];
-            }
-            _ => return packet,
+        if line.rest().starts_with('[') {
+            packet.push(parse_packet(line)?);
+        } else {
+            packet.push_num(line.number()?);
+        }
+        if !line.one_of(&[(",", true), ("]", false)])? {
+            return Ok(packet);
         }
     }
 }
 
+const EXAMPLE: &str = "[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]";
+
 pub struct Day13;
-impl Day for Day13 {
+impl ConsumingDay for Day13 {
     type Parsed = Vec<Packet>;
     type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("13"), Some("140")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .lines()
-            .filter_map(|line| {
-                if line.is_empty() {
-                    None
-                } else {
-                    Some(recurse_parse(&mut &line[1..line.len() - 1]))
-                }
+        Ok(lines(&input)
+            .filter(|line| !line.is_empty())
+            .map(|mut line| {
+                let packet = parse_packet(&mut line)?;
+                line.end()?;
+                Ok(packet)
             })
-            .collect())
+            .collect::<Result<_, ParseError>>()?)
     }
-    fn first(packets: Self::Parsed) -> Self::Output {
-        packets
+    fn first(packets: Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(packets
             .into_iter()
             .tuples()
             .enumerate()
@@ -113,59 +126,36 @@ impl Day for Day13 {
                     i + 1
                 }
             })
-            .sum()
+            .sum())
     }
-    fn second(mut packets: Self::Parsed) -> Self::Output {
+    fn second(mut packets: Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         let two = Packet::Parent(vec![Packet::Parent(vec![Packet::Number(2)])]);
         let six = Packet::Parent(vec![Packet::Parent(vec![Packet::Number(6)])]);
         packets.push(two.clone());
         packets.push(six.clone());
         packets.sort_unstable();
-        packets
+        Ok(packets
             .into_iter()
             .enumerate()
             .filter_map(|(i, p)| {
                 (p.cmp(&two) == Ordering::Equal || p.cmp(&six) == Ordering::Equal).then_some(i + 1)
             })
-            .product::<usize>()
+            .product::<usize>())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "[1,1,3,1,1]
-[1,1,5,1,1]
-
-[[1],[2,3,4]]
-[[1],4]
-
-[9]
-[[8,7,6]]
-
-[[4,4],4,4]
-[[4,4],4,4,4]
-
-[7,7,7,7]
-[7,7,7]
-
-[]
-[3]
-
-[[[]]]
-[[]]
-
-[1,[2,[3,[4,[5,6,7]]]],8,9]
-[1,[2,[3,[4,[5,6,0]]]],8,9]";
-    fn parsed() -> <Day13 as Day>::Parsed {
-        Day13::parse(INPUT.to_string()).unwrap()
+    fn parsed() -> <Day13 as ConsumingDay>::Parsed {
+        Day13::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day13::first(parsed()), 13);
+        assert_eq!(Day13::first(parsed(), &()).unwrap(), 13);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day13::second(parsed()), 140);
+        assert_eq!(Day13::second(parsed(), &()).unwrap(), 140);
     }
 }
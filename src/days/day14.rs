@@ -1,28 +1,14 @@
-use super::day::Day;
+use super::day::{Day, Example};
+use crate::helpers::parse::{lines, ParseError};
 use crate::helpers::LongBitArr;
 use ahash::AHashSet;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use itertools::{Itertools, MinMaxResult};
 
-pub trait SandSolver: CloneSandSolver {
-    fn first(&mut self) -> usize;
-    fn second(&mut self) -> usize;
-}
-pub trait CloneSandSolver {
-    fn clone_sand_solver(&self) -> Box<dyn SandSolver>;
-}
-impl<T> CloneSandSolver for T
-where
-    T: SandSolver + Clone + 'static,
-{
-    fn clone_sand_solver(&self) -> Box<dyn SandSolver> {
-        Box::new(self.clone())
-    }
-}
-impl Clone for Box<dyn SandSolver> {
-    fn clone(&self) -> Self {
-        self.clone_sand_solver()
-    }
+/// Both parts pour sand onto a copy of the map, so the solver can be reused.
+pub trait SandSolver {
+    fn first(&self) -> usize;
+    fn second(&self) -> usize;
 }
 #[derive(Debug, Clone)]
 pub struct ArraySandSolver<const XC: usize, const YC: usize> {
@@ -70,30 +56,32 @@ impl<const XC: usize, const YC: usize> ArraySandSolver<XC, YC> {
     }
 }
 impl<const XC: usize, const YC: usize> SandSolver for ArraySandSolver<XC, YC> {
-    fn first(&mut self) -> usize {
+    fn first(&self) -> usize {
+        let mut map = self.map;
         let mut sand = self.sand_start;
         let mut total_sand = 0;
         loop {
             if sand.1 >= self.bottom {
                 break;
-            } else if !self.map[sand.1 + 1].get(sand.0) {
+            } else if !map[sand.1 + 1].get(sand.0) {
                 sand.1 += 1;
-            } else if !self.map[sand.1 + 1].get(sand.0 - 1) {
+            } else if !map[sand.1 + 1].get(sand.0 - 1) {
                 sand = (sand.0 - 1, sand.1 + 1);
-            } else if !self.map[sand.1 + 1].get(sand.0 + 1) {
+            } else if !map[sand.1 + 1].get(sand.0 + 1) {
                 sand = (sand.0 + 1, sand.1 + 1);
             } else {
-                self.map[sand.1].set(sand.0);
+                map[sand.1].set(sand.0);
                 total_sand += 1;
                 sand = self.sand_start;
             }
         }
         total_sand
     }
-    fn second(&mut self) -> usize {
-        let init_blocks = self.map.iter().map(LongBitArr::count_ones).sum::<usize>();
-        Self::second_deep(&mut self.map, self.sand_start, self.bottom);
-        self.map.iter().map(LongBitArr::count_ones).sum::<usize>() - init_blocks
+    fn second(&self) -> usize {
+        let mut map = self.map;
+        let init_blocks = map.iter().map(LongBitArr::count_ones).sum::<usize>();
+        Self::second_deep(&mut map, self.sand_start, self.bottom);
+        map.iter().map(LongBitArr::count_ones).sum::<usize>() - init_blocks
     }
 }
 #[derive(Debug, Clone)]
@@ -121,27 +109,28 @@ impl SetSandSolver {
     }
 }
 impl SandSolver for SetSandSolver {
-    fn first(&mut self) -> usize {
+    fn first(&self) -> usize {
+        let mut map = self.map.clone();
         let mut sand = (500, 0);
         let mut total_sand = 0;
         loop {
             if sand.1 >= self.bottom {
                 break;
-            } else if !self.map.contains(&(sand.0, sand.1 + 1)) {
+            } else if !map.contains(&(sand.0, sand.1 + 1)) {
                 sand.1 += 1;
-            } else if !self.map.contains(&(sand.0 - 1, sand.1 + 1)) {
+            } else if !map.contains(&(sand.0 - 1, sand.1 + 1)) {
                 sand = (sand.0 - 1, sand.1 + 1);
-            } else if !self.map.contains(&(sand.0 + 1, sand.1 + 1)) {
+            } else if !map.contains(&(sand.0 + 1, sand.1 + 1)) {
                 sand = (sand.0 + 1, sand.1 + 1);
             } else {
-                self.map.insert(sand);
+                map.insert(sand);
                 total_sand += 1;
                 sand = (500, 0);
             }
         }
         total_sand
     }
-    fn second(&mut self) -> usize {
+    fn second(&self) -> usize {
         fn deep(map: &mut AHashSet<(usize, usize)>, sand: (usize, usize), bottom: usize) {
             if sand.1 > bottom + 1 {
                 return;
@@ -157,37 +146,38 @@ impl SandSolver for SetSandSolver {
                 deep(map, (sand.0 + 1, sand.1 + 1), bottom);
             }
         }
-        let before = self.map.len();
-        deep(&mut self.map, (500, 0), self.bottom);
-        self.map.len() - before
+        let mut map = self.map.clone();
+        deep(&mut map, (500, 0), self.bottom);
+        map.len() - self.map.len()
     }
 }
 
+const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
 pub struct Day14;
 impl Day for Day14 {
     type Parsed = Box<dyn SandSolver>;
     type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("24"), Some("93")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        let paths: Vec<Vec<(usize, usize)>> = input
-            .lines()
-            .map(|line| {
-                line.split(" -> ")
-                    .map(|xy| {
-                        xy.split(',')
-                            .map(|v| v.parse().unwrap())
-                            .collect_tuple()
-                            .unwrap()
-                    })
-                    .collect()
+        let paths: Vec<Vec<(usize, usize)>> = lines(&input)
+            .map(|mut line| {
+                line.separated(" -> ", |line| {
+                    let x = line.number()?;
+                    line.literal(",")?;
+                    Ok((x, line.number()?))
+                })
             })
-            .collect();
+            .collect::<Result<_, ParseError>>()?;
 
         let xs = paths.iter().flat_map(|path| path.iter().map(|p| p.0));
         let ys = paths.iter().flat_map(|path| path.iter().map(|p| p.1));
         let borders = match (xs.minmax(), ys.minmax()) {
             (MinMaxResult::MinMax(x1, x2), MinMaxResult::MinMax(y1, y2)) => ((x1, x2), (y1, y2)),
-            _ => panic!(),
+            _ => bail!("The scan needs rocks spanning more than one row and column"),
         };
 
         let expanded_width = (borders.0 .1 - borders.0 .0) + borders.1 .1 * 2 + 6;
@@ -208,28 +198,26 @@ impl Day for Day14 {
             _ => Box::new(SetSandSolver::parse(&paths, borders)),
         })
     }
-    fn first(mut solver: Self::Parsed) -> Self::Output {
-        solver.first()
+    fn first(solver: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(solver.first())
     }
-    fn second(mut solver: Self::Parsed) -> Self::Output {
-        solver.second()
+    fn second(solver: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(solver.second())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
     fn parsed() -> <Day14 as Day>::Parsed {
-        Day14::parse(INPUT.to_string()).unwrap()
+        Day14::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day14::first(parsed()), 24);
+        assert_eq!(Day14::first(&parsed(), &()).unwrap(), 24);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day14::second(parsed()), 93);
+        assert_eq!(Day14::second(&parsed(), &()).unwrap(), 93);
     }
 }
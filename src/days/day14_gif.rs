@@ -0,0 +1,204 @@
+use super::day::{ConsumingDay, Example};
+use crate::helpers::parse::{lines, ParseError};
+use anyhow::Result;
+use gif::{Encoder, Frame, Repeat};
+use itertools::Itertools;
+use std::{borrow::Cow, collections::HashSet, io::Write};
+
+#[allow(dead_code)]
+fn print_map(map: &HashSet<(usize, usize)>, left: usize, right: usize, top: usize, bottom: usize) {
+    for y in top..=bottom {
+        for x in left..=right {
+            if map.contains(&(x, y)) {
+                print!("#");
+            } else {
+                print!(".");
+            }
+        }
+        println!();
+    }
+    println!("-----------------------");
+}
+
+type Animation<'a> = Encoder<&'a mut dyn Write>;
+/// Size of the animation, centered on the source of the sand.
+const WIDTH: usize = 360;
+const HEIGHT: usize = 180;
+
+fn animation(out: &mut dyn Write) -> Result<Animation<'_>> {
+    let color_map = &[0xA9, 0xB9, 0xCB, 229, 157, 111, 0x52, 0x2A, 0x2B];
+    #[allow(clippy::cast_possible_truncation)]
+    let mut encoder = Encoder::new(out, WIDTH as u16, HEIGHT as u16, color_map)?;
+    encoder.set_repeat(Repeat::Infinite)?;
+    Ok(encoder)
+}
+
+/// Draws the rocks, and the sand that came to rest around them.
+fn write_frame(
+    encoder: &mut Animation<'_>,
+    map: &HashSet<(usize, usize)>,
+    rocks: &HashSet<(usize, usize)>,
+) -> Result<()> {
+    let mut state = vec![0; WIDTH * HEIGHT];
+    let left = 500 - WIDTH / 2;
+    for y in 0..HEIGHT {
+        for x in left..left + WIDTH {
+            if rocks.contains(&(x, y)) {
+                state[y * WIDTH + x - left] = 2;
+            } else if map.contains(&(x, y)) {
+                state[y * WIDTH + x - left] = 1;
+            }
+        }
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let frame = Frame {
+        width: WIDTH as u16,
+        height: HEIGHT as u16,
+        buffer: Cow::Borrowed(&state),
+        ..Default::default()
+    };
+    encoder.write_frame(&frame)?;
+    Ok(())
+}
+
+const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+pub struct Day14;
+impl ConsumingDay for Day14 {
+    type Parsed = (HashSet<(usize, usize)>, usize);
+    type Output = i32;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("24"), Some("93")])];
+
+    fn parse(input: String) -> Result<Self::Parsed> {
+        let paths: Vec<Vec<(usize, usize)>> = lines(&input)
+            .map(|mut line| {
+                line.separated(" -> ", |line| {
+                    let x = line.number()?;
+                    line.literal(",")?;
+                    Ok((x, line.number()?))
+                })
+            })
+            .collect::<Result<_, ParseError>>()?;
+        let mut map = HashSet::new();
+        let mut bottom = 0;
+        for path in paths {
+            for (a, b) in path.iter().tuple_windows() {
+                if a.0 == b.0 {
+                    let moves = if a.1 > b.1 { b.1..=a.1 } else { a.1..=b.1 };
+                    for y in moves {
+                        map.insert((a.0, y));
+                        bottom = bottom.max(y);
+                    }
+                } else {
+                    let moves = if a.0 > b.0 { b.0..=a.0 } else { a.0..=b.0 };
+                    for x in moves {
+                        map.insert((x, a.1));
+                        bottom = bottom.max(a.1);
+                    }
+                }
+            }
+        }
+
+        Ok((map, bottom))
+    }
+    fn first((mut map, bottom): Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        //print_map(&map, 400, 520, 0, bottom);
+        let mut sand = (500, 0);
+        let mut total_sand = 0;
+        loop {
+            if sand.1 >= bottom {
+                break;
+            } else if !map.contains(&(sand.0, sand.1 + 1)) {
+                sand.1 += 1;
+            } else if !map.contains(&(sand.0 - 1, sand.1 + 1)) {
+                sand = (sand.0 - 1, sand.1 + 1);
+            } else if !map.contains(&(sand.0 + 1, sand.1 + 1)) {
+                sand = (sand.0 + 1, sand.1 + 1);
+            } else {
+                // Can't sand no more
+                map.insert(sand);
+                total_sand += 1;
+                sand = (500, 0);
+            }
+        }
+        //print_map(&map, 400, 520, 0, bottom);
+
+        Ok(total_sand)
+    }
+    fn second((map, bottom): Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        pour(map, bottom, None)
+    }
+    const DUMP: Option<&'static str> = Some("gif");
+    /// The sand of part 2, one frame per grain that comes to rest.
+    fn dump((map, bottom): &Self::Parsed, _: &Self::Params, out: &mut dyn Write) -> Result<()> {
+        let mut encoder = animation(out)?;
+        pour(map.clone(), *bottom, Some(&mut encoder))?;
+        encoder.into_inner()?;
+        Ok(())
+    }
+}
+
+/// Pours sand until it blocks the source, drawing every grain that comes to
+/// rest into `encoder`.
+fn pour(
+    mut map: HashSet<(usize, usize)>,
+    bottom: usize,
+    encoder: Option<&mut Animation<'_>>,
+) -> Result<i32> {
+    let mut animation = encoder.map(|encoder| (encoder, map.clone()));
+
+    let mut sand = (500, 0);
+    let mut total_sand = 0;
+    loop {
+        if sand.1 == bottom + 1 {
+            if let Some((encoder, rocks)) = &mut animation {
+                write_frame(encoder, &map, rocks)?;
+            }
+            map.insert(sand);
+            total_sand += 1;
+            sand = (500, 0);
+        } else if !map.contains(&(sand.0, sand.1 + 1)) {
+            sand.1 += 1;
+        } else if !map.contains(&(sand.0 - 1, sand.1 + 1)) {
+            sand = (sand.0 - 1, sand.1 + 1);
+        } else if !map.contains(&(sand.0 + 1, sand.1 + 1)) {
+            sand = (sand.0 + 1, sand.1 + 1);
+        } else if map.contains(&sand) {
+            break;
+        } else {
+            if let Some((encoder, rocks)) = &mut animation {
+                write_frame(encoder, &map, rocks)?;
+            }
+            map.insert(sand);
+            total_sand += 1;
+            sand = (500, 0);
+        }
+    }
+    //print_map(&map, 400, 520, 0, bottom + 2);
+
+    Ok(total_sand)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn parsed() -> <Day14 as ConsumingDay>::Parsed {
+        Day14::parse(EXAMPLE.to_string()).unwrap()
+    }
+    #[test]
+    fn part1() {
+        assert_eq!(Day14::first(parsed(), &()).unwrap(), 24);
+    }
+    #[test]
+    fn part2() {
+        assert_eq!(Day14::second(parsed(), &()).unwrap(), 93);
+    }
+    #[test]
+    fn dump() {
+        let mut gif = vec![];
+        Day14::dump(&parsed(), &(), &mut gif).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+    }
+}
@@ -1,7 +1,12 @@
-#![allow(clippy::cast_possible_wrap, clippy::range_plus_one)]
-use super::day::Day;
+#![allow(
+    clippy::cast_possible_wrap,
+    clippy::range_plus_one,
+    clippy::manual_midpoint
+)]
+use super::day::{params, Day, Example};
+use crate::helpers::parse::{lines, ParseError};
 use crate::helpers::{MergedRange, RangeIntersect};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use itertools::Itertools;
 use smallvec::{smallvec, SmallVec};
 use std::collections::VecDeque;
@@ -29,37 +34,60 @@ impl SensorData {
     }
 }
 
-#[cfg(test)]
-const Y_TEST: i32 = 10;
-#[cfg(not(test))]
-const Y_TEST: i32 = 2_000_000;
+params! {
+    pub struct Params15 {
+        /// Row to count the positions without a beacon in, in part 1.
+        row: i32 = 2_000_000,
+        /// Largest coordinate the distress beacon can be at, in part 2.
+        max: i32 = 4_000_000,
+    }
+}
 
-#[cfg(test)]
-const MAX_TEST: i32 = 20;
-#[cfg(not(test))]
-const MAX_TEST: i32 = 4_000_000;
+const EXAMPLE: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3";
 
 pub struct Day15;
 impl Day for Day15 {
     type Parsed = Vec<SensorData>;
     type Output = usize;
+    type Params = Params15;
+    const EXAMPLES: &'static [Example] = &[Example {
+        params: &[("row", "10"), ("max", "20")],
+        ..Example::new(EXAMPLE, [Some("26"), Some("56000011")])
+    }];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .lines()
-            .map(|line| {
-                let nums: Vec<i32> = line
-                    .split(&['=', ',', ':'])
-                    .filter_map(|s| s.parse().ok())
-                    .collect();
-                SensorData::new((nums[0], nums[1]), (nums[2], nums[3]))
+        Ok(lines(&input)
+            .map(|mut line| {
+                line.literal("Sensor at x=")?;
+                let x = line.number()?;
+                line.literal(", y=")?;
+                let y = line.number()?;
+                line.literal(": closest beacon is at x=")?;
+                let beacon_x = line.number()?;
+                line.literal(", y=")?;
+                let beacon_y = line.number()?;
+                line.end()?;
+                Ok(SensorData::new((x, y), (beacon_x, beacon_y)))
             })
-            .collect())
+            .collect::<Result<_, ParseError>>()?)
     }
-    fn first(sensors: Self::Parsed) -> Self::Output {
+    fn first(sensors: &Self::Parsed, params: &Self::Params) -> Result<Self::Output> {
         let beacons_on_y: Vec<_> = sensors
             .iter()
-            .filter(|sensor| sensor.beacon.1 == Y_TEST)
+            .filter(|sensor| sensor.beacon.1 == params.row)
             .map(|sensor| sensor.beacon.0)
             .unique()
             .collect();
@@ -67,8 +95,8 @@ impl Day for Day15 {
         let merged_ranges: MergedRange<_> = sensors
             .iter()
             .filter_map(|sensor| {
-                if sensor.max_influences_y(Y_TEST) {
-                    let distance = sensor.manhattan - (sensor.pos.1 - Y_TEST).abs();
+                if sensor.max_influences_y(params.row) {
+                    let distance = sensor.manhattan - (sensor.pos.1 - params.row).abs();
                     Some((sensor.pos.0 - distance)..(sensor.pos.0 + distance + 1))
                 } else {
                     None
@@ -76,7 +104,7 @@ impl Day for Day15 {
             })
             .collect();
 
-        merged_ranges
+        Ok(merged_ranges
             .ranges()
             .into_iter()
             .map(|r| {
@@ -86,12 +114,12 @@ impl Day for Day15 {
                     .count();
                 (r.end - r.start) as usize - beacons_in_range
             })
-            .sum()
+            .sum())
     }
-    fn second(sensors: Self::Parsed) -> Self::Output {
+    fn second(sensors: &Self::Parsed, params: &Self::Params) -> Result<Self::Output> {
         sensors
             .iter()
-            .fold(VecDeque::from([0, MAX_TEST]), |mut acc, sensor| {
+            .fold(VecDeque::from([0, params.max]), |mut acc, sensor| {
                 acc.push_back(sensor.diagonal - sensor.manhattan - 1);
                 acc.push_back(sensor.diagonal - sensor.manhattan - 2);
                 acc.push_back(sensor.diagonal + sensor.manhattan + 1);
@@ -100,8 +128,8 @@ impl Day for Day15 {
             })
             .into_iter()
             .filter_map(|diag| {
-                if diag >= 0 && diag <= MAX_TEST * 2 {
-                    let max_range = diag.min(2 * MAX_TEST - diag);
+                if diag >= 0 && diag <= params.max * 2 {
+                    let max_range = diag.min(2 * params.max - diag);
                     Some((diag, smallvec![-max_range..max_range]))
                 } else {
                     None
@@ -109,7 +137,7 @@ impl Day for Day15 {
             })
             .unique()
             .filter_map(|(diag, mut range): (i32, SmallVec<[_; 2]>)| {
-                for sensor in &sensors {
+                for sensor in sensors {
                     if !(sensor.diagonal - sensor.manhattan > diag
                         || sensor.diagonal + sensor.manhattan < diag)
                     {
@@ -119,7 +147,7 @@ impl Day for Day15 {
                             .flat_map(|r| {
                                 [
                                     (0..(diag_test - sensor.manhattan - 1)),
-                                    ((diag_test + sensor.manhattan + 1)..MAX_TEST),
+                                    ((diag_test + sensor.manhattan + 1)..params.max),
                                 ]
                                 .iter()
                                 .filter_map(|test_range| r.intersect(test_range))
@@ -140,36 +168,23 @@ impl Day for Day15 {
                 4_000_000 * x + y
             })
             .next()
-            .unwrap()
+            .context("No position left for the distress beacon")
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-Sensor at x=9, y=16: closest beacon is at x=10, y=16
-Sensor at x=13, y=2: closest beacon is at x=15, y=3
-Sensor at x=12, y=14: closest beacon is at x=10, y=16
-Sensor at x=10, y=20: closest beacon is at x=10, y=16
-Sensor at x=14, y=17: closest beacon is at x=10, y=16
-Sensor at x=8, y=7: closest beacon is at x=2, y=10
-Sensor at x=2, y=0: closest beacon is at x=2, y=10
-Sensor at x=0, y=11: closest beacon is at x=2, y=10
-Sensor at x=20, y=14: closest beacon is at x=25, y=17
-Sensor at x=17, y=20: closest beacon is at x=21, y=22
-Sensor at x=16, y=7: closest beacon is at x=15, y=3
-Sensor at x=14, y=3: closest beacon is at x=15, y=3
-Sensor at x=20, y=1: closest beacon is at x=15, y=3";
     fn parsed() -> <Day15 as Day>::Parsed {
-        Day15::parse(INPUT.to_string()).unwrap()
+        Day15::parse(EXAMPLE.to_string()).unwrap()
     }
+    const PARAMS: Params15 = Params15 { row: 10, max: 20 };
     #[test]
     fn part1() {
-        assert_eq!(Day15::first(parsed()), 26);
+        assert_eq!(Day15::first(&parsed(), &PARAMS).unwrap(), 26);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day15::second(parsed()), 56000011);
+        assert_eq!(Day15::second(&parsed(), &PARAMS).unwrap(), 56_000_011);
     }
 }
@@ -1,21 +1,31 @@
-use super::day::Day;
+use super::day::{params, Day, Example};
+use crate::helpers::parse::{lines, Line, ParseError};
 use crate::helpers::{BitArray, IntoBitIterator};
 use ahash::AHashMap;
-use anyhow::Result;
+use anyhow::{ensure, Context, Result};
 use itertools::iproduct;
 use smallvec::SmallVec;
 use std::collections::HashMap;
 
-pub struct RoomSolver64 {
-    rooms: Vec<Room>,
+params! {
+    pub struct Params16 {
+        /// Minutes until the volcano erupts in part 1.
+        minutes: u32 = 30,
+        /// Minutes left after teaching the elephant in part 2.
+        minutes_part2: u32 = 26,
+    }
+}
+
+pub struct RoomSolver64<'a> {
+    rooms: &'a [Room],
     start: usize,
     weights: [u32; 64 * 64],
     flows: [u32; 64],
 }
-impl RoomSolver64 {
-    pub fn new(rooms: Vec<Room>, start: usize) -> Self {
-        let weights = Self::make_weights(&rooms);
-        let flows = Self::make_flows(&rooms);
+impl<'a> RoomSolver64<'a> {
+    pub fn new(rooms: &'a [Room], start: usize) -> Self {
+        let weights = Self::make_weights(rooms);
+        let flows = Self::make_flows(rooms);
         Self {
             rooms,
             start,
@@ -54,8 +64,8 @@ impl RoomSolver64 {
         }
         to_search
     }
-    fn solve_first(&mut self) -> u32 {
-        self.alone(30, self.start, self.make_to_search())
+    fn solve_first(&mut self, minutes: u32) -> u32 {
+        self.alone(minutes, self.start, self.make_to_search())
     }
     fn alone(&mut self, time: u32, key: usize, to_search: usize) -> u32 {
         if time == 0 {
@@ -75,17 +85,23 @@ impl RoomSolver64 {
         }
         max
     }
-    fn solve_second(&mut self) -> u32 {
+    fn solve_second(&mut self, minutes: u32) -> u32 {
         let to_search = self.make_to_search();
-        self.together(&mut AHashMap::new(), 26, self.start, to_search, true)
+        self.together(
+            &mut AHashMap::new(),
+            minutes,
+            self.start,
+            to_search,
+            Some(minutes),
+        )
     }
     fn together(
         &mut self,
-        memo: &mut AHashMap<(u32, usize, usize, bool), u32>,
+        memo: &mut AHashMap<(u32, usize, usize, Option<u32>), u32>,
         time: u32,
         key: usize,
         to_search: usize,
-        helper: bool,
+        helper: Option<u32>,
     ) -> u32 {
         if time == 0 {
             return 0;
@@ -104,8 +120,9 @@ impl RoomSolver64 {
                 max = max.max(deeper + self.flows[curr] * time_left);
             }
         }
-        if helper {
-            max = max.max(self.together(memo, 26, self.start, to_search, false));
+        // The helper starts with the whole time once we are done.
+        if let Some(helper_time) = helper {
+            max = max.max(self.together(memo, helper_time, self.start, to_search, None));
         }
         memo.insert((time, key, to_search, helper), max);
         max
@@ -118,77 +135,100 @@ pub struct Room {
     tunnels: SmallVec<[usize; 8]>,
 }
 pub struct TempRoom<'a> {
+    line: Line<'a>,
     flow: u32,
-    tunnels: SmallVec<[&'a str; 8]>,
+    /// Names of the tunnels, and where they are in the line.
+    tunnels: SmallVec<[(usize, &'a str); 8]>,
 }
 
+const EXAMPLE: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II";
+
 pub struct Day16;
 impl Day for Day16 {
     type Parsed = (Vec<Room>, usize);
     type Output = u32;
+    type Params = Params16;
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("1651"), Some("1707")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
         let mut names_to_i = HashMap::new();
 
-        let temp_rooms: Vec<TempRoom> = input
-            .lines()
+        let temp_rooms: Vec<TempRoom> = lines(&input)
             .enumerate()
-            .map(|(i, line)| {
-                let (flow, rest) = line[23..].split_once(';').unwrap();
-                names_to_i.insert(&line[6..8], i);
-
-                TempRoom {
-                    flow: flow.parse::<u32>().unwrap(),
-                    tunnels: rest[23..].trim_start().split(", ").collect(),
-                }
+            .map(|(i, mut line)| {
+                line.literal("Valve ")?;
+                names_to_i.insert(line.word()?, i);
+                line.literal(" has flow rate=")?;
+                let flow = line.number()?;
+                line.one_of(&[
+                    ("; tunnels lead to valves ", ()),
+                    ("; tunnel leads to valve ", ()),
+                ])?;
+                let tunnels = line.separated(", ", |line| Ok((line.pos(), line.word()?)))?;
+                Ok(TempRoom {
+                    line,
+                    flow,
+                    tunnels: tunnels.into_iter().collect(),
+                })
             })
-            .collect();
+            .collect::<Result<_, ParseError>>()?;
 
-        Ok((
-            temp_rooms
-                .iter()
-                .map(|r| Room {
+        let rooms = temp_rooms
+            .iter()
+            .map(|r| {
+                let tunnels = r.tunnels.iter().map(|&(pos, name)| {
+                    names_to_i
+                        .get(name)
+                        .copied()
+                        .ok_or_else(|| r.line.error_at(pos, name.len(), "a valve of the scan"))
+                });
+                Ok(Room {
                     flow: r.flow,
-                    tunnels: r.tunnels.iter().map(|t| names_to_i[t]).collect(),
+                    tunnels: tunnels.collect::<Result<_, ParseError>>()?,
                 })
-                .collect(),
-            *names_to_i.get(&"AA").unwrap(),
+            })
+            .collect::<Result<_, ParseError>>()?;
+        Ok((
+            rooms,
+            *names_to_i.get("AA").context("No valve AA to start at")?,
         ))
     }
-    fn first((rooms, start): Self::Parsed) -> Self::Output {
-        assert!(rooms.len() <= 64, "Input data too long");
-        let mut solver = RoomSolver64::new(rooms, start);
-        solver.solve_first()
+    fn first((rooms, start): &Self::Parsed, params: &Self::Params) -> Result<Self::Output> {
+        ensure!(rooms.len() <= 64, "Input data too long");
+        let mut solver = RoomSolver64::new(rooms, *start);
+        Ok(solver.solve_first(params.minutes))
     }
-    fn second((rooms, start): Self::Parsed) -> Self::Output {
-        assert!(rooms.len() <= 64, "Input data too long");
-        let mut solver = RoomSolver64::new(rooms, start);
-        solver.solve_second()
+    fn second((rooms, start): &Self::Parsed, params: &Self::Params) -> Result<Self::Output> {
+        ensure!(rooms.len() <= 64, "Input data too long");
+        let mut solver = RoomSolver64::new(rooms, *start);
+        Ok(solver.solve_second(params.minutes_part2))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-Valve BB has flow rate=13; tunnels lead to valves CC, AA
-Valve CC has flow rate=2; tunnels lead to valves DD, BB
-Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-Valve EE has flow rate=3; tunnels lead to valves FF, DD
-Valve FF has flow rate=0; tunnels lead to valves EE, GG
-Valve GG has flow rate=0; tunnels lead to valves FF, HH
-Valve HH has flow rate=22; tunnel leads to valve GG
-Valve II has flow rate=0; tunnels lead to valves AA, JJ
-Valve JJ has flow rate=21; tunnel leads to valve II";
     fn parsed() -> <Day16 as Day>::Parsed {
-        Day16::parse(INPUT.to_string()).unwrap()
+        Day16::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day16::first(parsed()), 1651);
+        assert_eq!(Day16::first(&parsed(), &Params16::default()).unwrap(), 1651);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day16::second(parsed()), 1707);
+        assert_eq!(
+            Day16::second(&parsed(), &Params16::default()).unwrap(),
+            1707
+        );
     }
 }
@@ -1,4 +1,5 @@
-use super::day::Day;
+use super::day::{params, Day, Example};
+use crate::helpers::parse::lines;
 use ahash::AHashMap;
 use anyhow::Result;
 use std::collections::hash_map::{DefaultHasher, Entry};
@@ -42,7 +43,7 @@ impl Shaper {
     const fn new(shape: Shape) -> Self {
         Self(shape.int())
     }
-    fn wind(&mut self, dir: Direction, map: u32) {
+    const fn wind(&mut self, dir: Direction, map: u32) {
         match dir {
             Direction::Right => {
                 if self.0 & 0b0000_0001_0000_0001_0000_0001_0000_0001 == 0 && self.0 >> 1 & map == 0
@@ -87,14 +88,34 @@ fn map_display(m: &[u8]) {
     println!("________________");
 }
 
+params! {
+    pub struct Params17 {
+        /// Rocks to let fall in part 1.
+        rocks: usize = 2022,
+        /// Rocks to let fall in part 2.
+        rocks_part2: usize = 1_000_000_000_000,
+    }
+}
+
+const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
 pub struct Day17;
 impl Day for Day17 {
     type Parsed = Vec<Direction>;
     type Output = usize;
+    type Params = Params17;
+    const EXAMPLES: &'static [Example] =
+        &[Example::new(EXAMPLE, [Some("3068"), Some("1514285714288")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .trim_end()
+        let mut input = lines(&input);
+        let line = input.expect_line("the jet pattern")?;
+        line.check_chars("'<' or '>'", |c| matches!(c, '<' | '>'))?;
+        if line.is_empty() {
+            return Err(line.error("'<' or '>'").into());
+        }
+        Ok(line
+            .text()
             .as_bytes()
             .iter()
             .map(|c| match c {
@@ -103,49 +124,66 @@ impl Day for Day17 {
             })
             .collect())
     }
-    fn first(moves: Self::Parsed) -> Self::Output {
-        let mut move_it = moves.into_iter().enumerate().cycle();
-        let mut shape_it = ALL_SHAPES.iter().cycle();
-        let mut map: Vec<u8> = vec![];
+    const SOLVES_BOTH: bool = true;
 
-        for _ in 0..2022 {
-            simulate_block(&mut map, &mut move_it, &mut shape_it);
+    fn first(moves: &Self::Parsed, params: &Self::Params) -> Result<Self::Output> {
+        Ok(tower_heights(moves, [params.rocks])[0])
+    }
+    fn second(moves: &Self::Parsed, params: &Self::Params) -> Result<Self::Output> {
+        Ok(tower_heights(moves, [params.rocks_part2])[0])
+    }
+    fn both(moves: &Self::Parsed, params: &Self::Params) -> Result<(Self::Output, Self::Output)> {
+        if params.rocks > params.rocks_part2 {
+            return Ok((Self::first(moves, params)?, Self::second(moves, params)?));
         }
-        map.len()
+        Ok(tower_heights(moves, [params.rocks, params.rocks_part2]).into())
     }
-    fn second(moves: Self::Parsed) -> Self::Output {
-        let mut move_it = moves.into_iter().enumerate().cycle();
-        let mut shape_it = ALL_SHAPES.iter().cycle();
-        let mut map: Vec<u8> = vec![];
+}
 
-        let mut hash: AHashMap<_, (usize, usize)> = AHashMap::new();
-        let mut len_from_cycles = 0;
-        let mut rocks = 0;
-        while rocks < 1_000_000_000_000usize {
-            let (wind_pos, shape_pos) = simulate_block(&mut map, &mut move_it, &mut shape_it);
+/// Height of the tower after each of the ascending numbers of rocks. Repeating
+/// cycles are only skipped on the way to the last one.
+fn tower_heights<const N: usize>(moves: &[Direction], targets: [usize; N]) -> [usize; N] {
+    let mut move_it = moves.iter().copied().enumerate().cycle();
+    let mut shape_it = ALL_SHAPES.iter().cycle();
+    let mut map: Vec<u8> = vec![];
+    let mut heights = [0; N];
+    let mut next_target = 0;
 
-            if len_from_cycles == 0 {
-                let mut hasher = DefaultHasher::new();
-                for &u in &map[map.len().saturating_sub(64)..] {
-                    u.hash(&mut hasher);
-                }
+    let mut hash: AHashMap<_, (usize, usize)> = AHashMap::new();
+    let mut len_from_cycles = 0;
+    let mut rocks = 0;
+    loop {
+        while next_target < N && rocks == targets[next_target] {
+            heights[next_target] = map.len() + len_from_cycles;
+            next_target += 1;
+        }
+        if next_target == N {
+            break;
+        }
+        let (wind_pos, shape_pos) = simulate_block(&mut map, &mut move_it, &mut shape_it);
 
-                match hash.entry((hasher.finish(), wind_pos, shape_pos)) {
-                    Entry::Occupied(entry) => {
-                        let cycle_num_increase = rocks - entry.get().1;
-                        let cycles_to_skip = (1_000_000_000_000usize - rocks) / cycle_num_increase;
-                        len_from_cycles = cycles_to_skip * (map.len() - entry.get().0);
-                        rocks += cycles_to_skip * cycle_num_increase;
-                    }
-                    Entry::Vacant(entry) => {
-                        entry.insert((map.len(), rocks));
-                    }
+        if len_from_cycles == 0 && next_target == N - 1 {
+            let mut hasher = DefaultHasher::new();
+            for &u in &map[map.len().saturating_sub(64)..] {
+                u.hash(&mut hasher);
+            }
+
+            match hash.entry((hasher.finish(), wind_pos, shape_pos)) {
+                Entry::Occupied(entry) => {
+                    let cycle_num_increase = rocks - entry.get().1;
+                    // `rocks + 1` rocks have fallen by now, don't skip past the target.
+                    let cycles_to_skip = (targets[N - 1] - rocks - 1) / cycle_num_increase;
+                    len_from_cycles = cycles_to_skip * (map.len() - entry.get().0);
+                    rocks += cycles_to_skip * cycle_num_increase;
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert((map.len(), rocks));
                 }
             }
-            rocks += 1;
         }
-        map.len() + len_from_cycles
+        rocks += 1;
     }
+    heights
 }
 
 fn simulate_block<'a, I: Iterator<Item = (usize, Direction)>, J: Iterator<Item = &'a Shape>>(
@@ -178,16 +216,66 @@ fn simulate_block<'a, I: Iterator<Item = (usize, Direction)>, J: Iterator<Item =
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
     fn parsed() -> <Day17 as Day>::Parsed {
-        Day17::parse(INPUT.to_string()).unwrap()
+        Day17::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day17::first(parsed()), 3068);
+        assert_eq!(Day17::first(&parsed(), &Params17::default()).unwrap(), 3068);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day17::second(parsed()), 1514285714288);
+        assert_eq!(
+            Day17::second(&parsed(), &Params17::default()).unwrap(),
+            1_514_285_714_288
+        );
+    }
+    #[test]
+    fn empty_pattern() {
+        let error = Day17::parse("\n".to_string()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected '<' or '>' at line 1, column 1, found the end of the line"
+        );
+    }
+    #[test]
+    fn both() {
+        assert_eq!(
+            Day17::both(&parsed(), &Params17::default()).unwrap(),
+            (3068, 1_514_285_714_288)
+        );
+    }
+    #[test]
+    fn cycle_boundaries() {
+        let moves = parsed();
+        let mut map = vec![];
+        let (mut move_it, mut shape_it) = (
+            moves.iter().copied().enumerate().cycle(),
+            ALL_SHAPES.iter().cycle(),
+        );
+        let mut brute_force = vec![0];
+        for _ in 0..400 {
+            simulate_block(&mut map, &mut move_it, &mut shape_it);
+            brute_force.push(map.len());
+        }
+        // The example repeats every 35 rocks.
+        for rocks in (100..400).step_by(7).chain([122, 123, 158]) {
+            let params = Params17 {
+                rocks: rocks / 2,
+                rocks_part2: rocks,
+            };
+            let expected = (brute_force[rocks / 2], brute_force[rocks]);
+            assert_eq!(
+                Day17::first(&moves, &params).unwrap(),
+                expected.0,
+                "{rocks}"
+            );
+            assert_eq!(
+                Day17::second(&moves, &params).unwrap(),
+                expected.1,
+                "{rocks}"
+            );
+            assert_eq!(Day17::both(&moves, &params).unwrap(), expected, "{rocks}");
+        }
     }
 }
@@ -1,6 +1,7 @@
-use super::day::Day;
+use super::day::{Day, Example};
+use crate::helpers::parse::{lines, ParseError};
 use ahash::{HashSet, HashSetExt};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use itertools::Itertools;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -26,27 +27,43 @@ impl Point {
     }
 }
 
+const EXAMPLE: &str = "2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5";
+
 pub struct Day18;
 impl Day for Day18 {
     type Parsed = HashSet<Point>;
     type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("64"), Some("58")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        let test = input
-            .lines()
-            .map(|line| {
-                let (x, y, z) = line
-                    .split(',')
-                    .map(|i| i.parse().unwrap())
-                    .next_tuple()
-                    .unwrap();
-                Point { x, y, z }
+        let test = lines(&input)
+            .map(|mut line| {
+                let x = line.number()?;
+                line.literal(",")?;
+                let y = line.number()?;
+                line.literal(",")?;
+                let z = line.number()?;
+                line.end()?;
+                Ok(Point { x, y, z })
             })
-            .collect();
+            .collect::<Result<_, ParseError>>()?;
         Ok(test)
     }
-    fn first(points: Self::Parsed) -> Self::Output {
-        points
+    fn first(points: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(points
             .iter()
             .map(|p| {
                 [
@@ -61,12 +78,27 @@ impl Day for Day18 {
                 .map(|&d| usize::from(!points.contains(&p.add(d))))
                 .sum::<usize>()
             })
-            .sum()
+            .sum())
     }
-    fn second(points: Self::Parsed) -> Self::Output {
-        let mut xs = points.iter().map(|p| p.x).minmax().into_option().unwrap();
-        let mut ys = points.iter().map(|p| p.y).minmax().into_option().unwrap();
-        let mut zs = points.iter().map(|p| p.z).minmax().into_option().unwrap();
+    fn second(points: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        let mut xs = points
+            .iter()
+            .map(|p| p.x)
+            .minmax()
+            .into_option()
+            .context("No cubes")?;
+        let mut ys = points
+            .iter()
+            .map(|p| p.y)
+            .minmax()
+            .into_option()
+            .context("No cubes")?;
+        let mut zs = points
+            .iter()
+            .map(|p| p.z)
+            .minmax()
+            .into_option()
+            .context("No cubes")?;
 
         xs = (xs.0 - 1, xs.1 + 1);
         ys = (ys.0 - 1, ys.1 + 1);
@@ -97,42 +129,28 @@ impl Day for Day18 {
                 {
                     if points.contains(&next_visit) {
                         touched_walls += 1;
-                    } else if !visited.contains(&next_visit) {
+                    } else if visited.insert(next_visit) {
                         to_visit.push(next_visit);
-                        visited.insert(next_visit);
                     }
                 }
             }
         }
-        touched_walls
+        Ok(touched_walls)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "2,2,2
-1,2,2
-3,2,2
-2,1,2
-2,3,2
-2,2,1
-2,2,3
-2,2,4
-2,2,6
-1,2,5
-3,2,5
-2,1,5
-2,3,5";
     fn parsed() -> <Day18 as Day>::Parsed {
-        Day18::parse(INPUT.to_string()).unwrap()
+        Day18::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day18::first(parsed()), 64);
+        assert_eq!(Day18::first(&parsed(), &()).unwrap(), 64);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day18::second(parsed()), 58);
+        assert_eq!(Day18::second(&parsed(), &()).unwrap(), 58);
     }
 }
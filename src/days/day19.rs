@@ -1,5 +1,6 @@
 #![allow(clippy::cast_lossless, clippy::cast_possible_truncation)]
-use super::day::Day;
+use super::day::{params, Day, Example};
+use crate::helpers::parse::{lines, ParseError};
 use ahash::{HashSet, HashSetExt};
 use anyhow::Result;
 use rayon::prelude::*;
@@ -105,7 +106,7 @@ const fn maxvals(time: u32, bots: u32) -> u32 {
         return bots * time;
     }
     let right = 2 * bots + (time - 1);
-    if time % 2 == 0 {
+    if time.is_multiple_of(2) {
         time / 2 * right
     } else {
         time / 2 * right + right / 2
@@ -120,7 +121,9 @@ fn possible_max_vals(factory: Factory, time: u32, mut bots: Robots, money: Money
 
     let mut geodes = money.geode;
     for i in (0..time).rev() {
-        ore_money.iter_mut().for_each(|m| *m += bots.ore as u32);
+        for m in &mut ore_money {
+            *m += bots.ore as u32;
+        }
         clay_money += bots.clay as u32;
         obs_money += bots.obsidian as u32;
         geodes += bots.geode as u32;
@@ -222,78 +225,103 @@ fn factory_loop(factory: Factory, max_time: u8) -> u32 {
     max
 }
 
+params! {
+    pub struct Params19 {
+        /// Minutes to open geodes in, in part 1.
+        minutes: u8 = 24,
+        /// Minutes to open geodes in, in part 2.
+        minutes_part2: u8 = 32,
+        /// Blueprints that survived the elephants, in part 2.
+        blueprints_part2: usize = 3,
+    }
+}
+
+/// The example of the puzzle text, with one blueprint per line like the inputs.
+const EXAMPLE: &str = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
+
 pub struct Day19;
 impl Day for Day19 {
     type Parsed = Vec<Factory>;
     type Output = u32;
+    type Params = Params19;
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("33"), Some("3472")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .lines()
-            .map(|line| {
-                let mut vals = line.split([' ', ':']).filter_map(|i| i.parse::<u32>().ok());
-                Factory {
-                    id: vals.next().unwrap(),
+        Ok(lines(&input)
+            .map(|mut line| {
+                line.literal("Blueprint ")?;
+                let id = line.number()?;
+                line.literal(": Each ore robot costs ")?;
+                let ore = line.number()?;
+                line.literal(" ore. Each clay robot costs ")?;
+                let clay = line.number()?;
+                line.literal(" ore. Each obsidian robot costs ")?;
+                let obsidian = (line.number()?, {
+                    line.literal(" ore and ")?;
+                    line.number()?
+                });
+                line.literal(" clay. Each geode robot costs ")?;
+                let geode = (line.number()?, {
+                    line.literal(" ore and ")?;
+                    line.number()?
+                });
+                line.literal(" obsidian.")?;
+                line.end()?;
+                Ok(Factory {
+                    id,
                     ore: Money {
-                        ore: vals.next().unwrap(),
+                        ore,
                         ..Default::default()
                     },
                     clay: Money {
-                        ore: vals.next().unwrap(),
+                        ore: clay,
                         ..Default::default()
                     },
                     obsidian: Money {
-                        ore: vals.next().unwrap(),
-                        clay: vals.next().unwrap(),
+                        ore: obsidian.0,
+                        clay: obsidian.1,
                         ..Default::default()
                     },
                     geode: Money {
-                        ore: vals.next().unwrap(),
-                        obsidian: vals.next().unwrap(),
+                        ore: geode.0,
+                        obsidian: geode.1,
                         ..Default::default()
                     },
-                }
+                })
             })
-            .collect())
+            .collect::<Result<_, ParseError>>()?)
     }
-    fn first(factories: Self::Parsed) -> Self::Output {
-        factories
+    fn first(factories: &Self::Parsed, params: &Self::Params) -> Result<Self::Output> {
+        Ok(factories
             .par_iter()
-            .map(|factory| factory_loop(*factory, 24) * factory.id)
-            .sum()
+            .map(|factory| factory_loop(*factory, params.minutes) * factory.id)
+            .sum())
     }
-    fn second(factories: Self::Parsed) -> Self::Output {
-        factories
+    fn second(factories: &Self::Parsed, params: &Self::Params) -> Result<Self::Output> {
+        Ok(factories
             .par_iter()
-            .take(3)
-            .map(|factory| factory_loop(*factory, 32))
-            .product()
+            .take(params.blueprints_part2)
+            .map(|factory| factory_loop(*factory, params.minutes_part2))
+            .product())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "Blueprint 1:
-  Each ore robot costs 4 ore.
-  Each clay robot costs 2 ore.
-  Each obsidian robot costs 3 ore and 14 clay.
-  Each geode robot costs 2 ore and 7 obsidian.
-
-Blueprint 2:
-  Each ore robot costs 2 ore.
-  Each clay robot costs 3 ore.
-  Each obsidian robot costs 3 ore and 8 clay.
-  Each geode robot costs 3 ore and 12 obsidian.";
     fn parsed() -> <Day19 as Day>::Parsed {
-        Day19::parse(INPUT.replace("\n  ", " ").replace("\n\n", "\n").to_string()).unwrap()
+        Day19::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day19::first(parsed()), 33);
+        assert_eq!(Day19::first(&parsed(), &Params19::default()).unwrap(), 33);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day19::second(parsed()), 3472);
+        assert_eq!(
+            Day19::second(&parsed(), &Params19::default()).unwrap(),
+            3472
+        );
     }
 }
@@ -1,6 +1,6 @@
-use super::day::Day;
+use super::day::{Day, Example};
+use crate::helpers::parse::{lines, ParseError};
 use anyhow::Result;
-use itertools::Itertools;
 use std::convert::TryFrom;
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -26,24 +26,31 @@ impl Shape {
     pub const WIN: Self = Self::Scissors;
 }
 
+const EXAMPLE: &str = "A Y
+B X
+C Z";
+
 pub struct Day2;
 impl Day for Day2 {
     type Parsed = Vec<(Shape, Shape)>;
     type Output = i32;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("15"), Some("12")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .filter_map(|s| s.try_into().ok())
-                    .next_tuple()
-                    .unwrap()
+        use Shape::*;
+        Ok(lines(&input)
+            .map(|mut line| {
+                let opponent = line.one_of(&[("A", Rock), ("B", Paper), ("C", Scissors)])?;
+                line.literal(" ")?;
+                let response = line.one_of(&[("X", Rock), ("Y", Paper), ("Z", Scissors)])?;
+                line.end()?;
+                Ok((opponent, response))
             })
-            .collect())
+            .collect::<Result<_, ParseError>>()?)
     }
-    fn first(lines: Self::Parsed) -> Self::Output {
-        lines
+    fn first(lines: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(lines
             .iter()
             .map(|shapes| {
                 match shapes.1 {
@@ -64,10 +71,10 @@ impl Day for Day2 {
                     },
                 }
             })
-            .sum()
+            .sum())
     }
-    fn second(lines: Self::Parsed) -> Self::Output {
-        lines
+    fn second(lines: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(lines
             .iter()
             .map(|shapes| {
                 match shapes.1 {
@@ -88,25 +95,22 @@ impl Day for Day2 {
                     },
                 }
             })
-            .sum()
+            .sum())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "A Y
-B X
-C Z";
     fn parsed() -> <Day2 as Day>::Parsed {
-        Day2::parse(INPUT.to_string()).unwrap()
+        Day2::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day2::first(parsed()), 15);
+        assert_eq!(Day2::first(&parsed(), &()).unwrap(), 15);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day2::second(parsed()), 12);
+        assert_eq!(Day2::second(&parsed(), &()).unwrap(), 12);
     }
 }
@@ -1,41 +1,67 @@
-#![allow(clippy::cast_possible_truncation)]
-use super::day::Day;
-use anyhow::Result;
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+use super::day::{params, ConsumingDay, Example};
+use crate::helpers::parse::{lines, ParseError};
+use anyhow::{Context, Result};
 use std::collections::VecDeque;
 
+params! {
+    pub struct Params20 {
+        /// Decryption key the numbers are multiplied with in part 2.
+        key: i64 = 811_589_153,
+        /// Rounds of mixing in part 2.
+        rounds: usize = 10,
+    }
+}
+
+const EXAMPLE: &str = "1
+2
+-3
+3
+-2
+0
+4";
+
 pub struct Day20;
-impl Day for Day20 {
+impl ConsumingDay for Day20 {
     type Parsed = VecDeque<(i64, usize)>;
     type Output = i64;
+    type Params = Params20;
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("3"), Some("1623178306")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .lines()
+        Ok(lines(&input)
             .enumerate()
-            .map(|(i, line)| (line.parse().unwrap(), i))
-            .collect())
+            .map(|(i, mut line)| {
+                let number = line.number()?;
+                line.end()?;
+                Ok((number, i))
+            })
+            .collect::<Result<_, ParseError>>()?)
     }
-    fn first(mut data: Self::Parsed) -> Self::Output {
+    fn first(mut data: Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         shuffle(&mut data);
         key(&data)
     }
-    fn second(mut data: Self::Parsed) -> Self::Output {
+    fn second(mut data: Self::Parsed, params: &Self::Params) -> Result<Self::Output> {
         for d in &mut data {
-            d.0 *= 811_589_153;
+            d.0 *= params.key;
         }
-        for _ in 0..10 {
+        for _ in 0..params.rounds {
             shuffle(&mut data);
         }
         key(&data)
     }
 }
 
-fn key(data: &VecDeque<(i64, usize)>) -> i64 {
-    let zero_pos = data.iter().position(|(d, _)| *d == 0).unwrap();
-    [1000, 2000, 3000]
+fn key(data: &VecDeque<(i64, usize)>) -> Result<i64> {
+    let zero_pos = data
+        .iter()
+        .position(|(d, _)| *d == 0)
+        .context("No 0 in the file")?;
+    Ok([1000, 2000, 3000]
         .iter()
         .map(|d| data[(zero_pos + d) % data.len()].0)
-        .sum()
+        .sum())
 }
 
 fn shuffle(data: &mut VecDeque<(i64, usize)>) {
@@ -50,22 +76,18 @@ fn shuffle(data: &mut VecDeque<(i64, usize)>) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "1
-2
--3
-3
--2
-0
-4";
-    fn parsed() -> <Day20 as Day>::Parsed {
-        Day20::parse(INPUT.to_string()).unwrap()
+    fn parsed() -> <Day20 as ConsumingDay>::Parsed {
+        Day20::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day20::first(parsed()), 3);
+        assert_eq!(Day20::first(parsed(), &Params20::default()).unwrap(), 3);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day20::second(parsed()), 1623178306);
+        assert_eq!(
+            Day20::second(parsed(), &Params20::default()).unwrap(),
+            1_623_178_306
+        );
     }
 }
@@ -1,6 +1,7 @@
-use super::day::Day;
+use super::day::{Day, Example};
+use crate::helpers::parse::{lines, ParseError};
 use ahash::HashMap;
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub enum Operator {
@@ -82,8 +83,8 @@ impl Monkey {
             Self::Number(_) => false,
         }
     }
-    fn backwards_eq(&self, monkeys: &HashMap<String, Self>, eq: i64) -> i64 {
-        match self {
+    fn backwards_eq(&self, monkeys: &HashMap<String, Self>, eq: i64) -> Result<i64> {
+        Ok(match self {
             Self::Number(n) => *n,
             Self::Operation(a, op, b) => {
                 if a == "humn" {
@@ -94,55 +95,77 @@ impl Monkey {
                     op.backwards_eq(eq, side, true)
                 } else if monkeys[a].has_human(monkeys) {
                     let side = monkeys[b].calculate(monkeys);
-                    monkeys[a].backwards_eq(monkeys, op.backwards_eq(eq, side, false))
+                    monkeys[a].backwards_eq(monkeys, op.backwards_eq(eq, side, false))?
                 } else if monkeys[b].has_human(monkeys) {
                     let side = monkeys[a].calculate(monkeys);
-                    monkeys[b].backwards_eq(monkeys, op.backwards_eq(eq, side, true))
+                    monkeys[b].backwards_eq(monkeys, op.backwards_eq(eq, side, true))?
                 } else {
-                    panic!("Neither monkey depends on numan. Invalid input: {a} {b} -> {eq}")
+                    bail!("Neither monkey depends on numan. Invalid input: {a} {b} -> {eq}")
                 }
             }
-        }
+        })
     }
 }
 
+const EXAMPLE: &str = "root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32";
+
 pub struct Day21;
 impl Day for Day21 {
     type Parsed = HashMap<String, Monkey>;
     type Output = i64;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("152"), Some("301")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .lines()
-            .map(|line| {
-                let parts: Vec<_> = line.split(' ').collect();
-                (
-                    parts[0][0..4].to_string(),
-                    match parts.len() {
-                        2 => Monkey::Number(parts[1].parse().unwrap()),
-                        _ => Monkey::Operation(
-                            parts[1].to_string(),
-                            parts[2].chars().next().unwrap().try_into().unwrap(),
-                            parts[3].to_string(),
-                        ),
-                    },
-                )
+        Ok(lines(&input)
+            .map(|mut line| {
+                let name = line.word()?.to_string();
+                line.literal(": ")?;
+                let monkey = if line.rest().starts_with(|c: char| c.is_ascii_digit()) {
+                    Monkey::Number(line.number()?)
+                } else {
+                    let a = line.word()?.to_string();
+                    let operator = line.one_of(&[
+                        (" + ", Operator::Add),
+                        (" - ", Operator::Sub),
+                        (" * ", Operator::Mult),
+                        (" / ", Operator::Div),
+                    ])?;
+                    Monkey::Operation(a, operator, line.word()?.to_string())
+                };
+                line.end()?;
+                Ok((name, monkey))
             })
-            .collect())
+            .collect::<Result<_, ParseError>>()?)
     }
-    fn first(monkeys: Self::Parsed) -> Self::Output {
-        monkeys["root"].calculate(&monkeys)
+    fn first(monkeys: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        let root = monkeys.get("root").context("No root monkey")?;
+        Ok(root.calculate(monkeys))
     }
-    fn second(monkeys: Self::Parsed) -> Self::Output {
-        match monkeys.get("root").unwrap() {
+    fn second(monkeys: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        match monkeys.get("root").context("No root monkey")? {
             Monkey::Operation(a, _, b) => {
-                if monkeys[a].has_human(&monkeys) {
-                    monkeys[a].backwards_eq(&monkeys, monkeys[b].calculate(&monkeys))
+                if monkeys[a].has_human(monkeys) {
+                    monkeys[a].backwards_eq(monkeys, monkeys[b].calculate(monkeys))
                 } else {
-                    monkeys[b].backwards_eq(&monkeys, monkeys[a].calculate(&monkeys))
+                    monkeys[b].backwards_eq(monkeys, monkeys[a].calculate(monkeys))
                 }
             }
-            Monkey::Number(n) => *n,
+            Monkey::Number(n) => Ok(*n),
         }
     }
 }
@@ -150,30 +173,15 @@ impl Day for Day21 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "root: pppw + sjmn
-dbpl: 5
-cczh: sllz + lgvd
-zczc: 2
-ptdq: humn - dvpt
-dvpt: 3
-lfqf: 4
-humn: 5
-ljgn: 2
-sjmn: drzm * dbpl
-sllz: 4
-pppw: cczh / lfqf
-lgvd: ljgn * ptdq
-drzm: hmdt - zczc
-hmdt: 32";
     fn parsed() -> <Day21 as Day>::Parsed {
-        Day21::parse(INPUT.to_string()).unwrap()
+        Day21::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day21::first(parsed()), 152);
+        assert_eq!(Day21::first(&parsed(), &()).unwrap(), 152);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day21::second(parsed()), 301);
+        assert_eq!(Day21::second(&parsed(), &()).unwrap(), 301);
     }
 }
@@ -1,10 +1,9 @@
-use super::day::Day;
+use super::day::{Day, Example};
 use crate::helpers::grid2d::{Direction4Way, Position2D, Turn};
-use anyhow::Result;
+use crate::helpers::parse::lines;
+use anyhow::{Context, Result};
 use itertools::Itertools;
-use lazy_static::lazy_static;
 use ndarray::Array2;
-use regex::Regex;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Point {
@@ -22,22 +21,36 @@ pub enum Movement {
 type Dir = Direction4Way;
 type Pos = Position2D<usize>;
 
-lazy_static! {
-    static ref RE: Regex = Regex::new(r"(\d+)|([A-Z])").unwrap();
-}
+const EXAMPLE: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5";
 
 pub struct Day22;
 impl Day for Day22 {
     type Parsed = (Array2<Point>, Vec<Movement>);
     type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("6032"), None])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        let (map, path) = input.split_once("\n\n").unwrap();
-        let width = map.lines().map(str::len).max().unwrap();
-        let height = map.lines().count();
-        let mut array = Array2::<Point>::default((height, width));
-        for (y, line) in map.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
+        let mut input = lines(&input);
+        let map: Vec<_> = input.paragraph().collect();
+        let width = map.iter().map(|line| line.text().len()).max().unwrap_or(0);
+        let mut array = Array2::<Point>::default((map.len(), width));
+        for (y, line) in map.iter().enumerate() {
+            line.check_chars("' ', '.' or '#'", |c| matches!(c, ' ' | '.' | '#'))?;
+            for (x, c) in line.text().chars().enumerate() {
                 match c {
                     '.' => array[[y, x]] = Point::Open,
                     '#' => array[[y, x]] = Point::Wall,
@@ -45,43 +58,43 @@ impl Day for Day22 {
                 }
             }
         }
-        let movements: Vec<_> = RE
-            .captures_iter(path)
-            .map(|p| {
-                if let Some(n) = p.get(1) {
-                    Movement::Num(n.as_str().parse().unwrap())
-                } else if let Some(d) = p.get(2) {
-                    Movement::Turn(match d.as_str() {
-                        "L" => Turn::Left,
-                        _ => Turn::Right,
-                    })
-                } else {
-                    panic!()
-                }
-            })
-            .collect();
+        let mut path = input.expect_line("the path")?;
+        let mut movements = vec![];
+        while !path.is_empty() {
+            if path.rest().starts_with(|c: char| c.is_ascii_digit()) {
+                movements.push(Movement::Num(path.number()?));
+            } else {
+                movements.push(path.one_of(&[
+                    ("L", Movement::Turn(Turn::Left)),
+                    ("R", Movement::Turn(Turn::Right)),
+                ])?);
+            }
+        }
         Ok((array, movements))
     }
-    fn first((array, movements): Self::Parsed) -> Self::Output {
+    fn first((array, movements): &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         let top_left_x = {
             let row = array.row(0);
-            row.iter().find_position(|&&p| p == Point::Open).unwrap().0
+            row.iter()
+                .find_position(|&&p| p == Point::Open)
+                .context("No open tile in the first row")?
+                .0
         };
         let mut current_pos = Pos::new(top_left_x, 0);
         let mut direction = Dir::Right;
-        for mov in movements {
+        for &mov in movements {
             match mov {
                 Movement::Turn(turn) => direction = direction + turn,
                 Movement::Num(num) => {
                     for _ in 0..num {
-                        current_pos = move_by(current_pos, direction, &array);
+                        current_pos = move_by(current_pos, direction, array);
                     }
                 }
             }
         }
-        (current_pos.y + 1) * 1000 + (current_pos.x + 1) * 4 + direction as usize
+        Ok((current_pos.y + 1) * 1000 + (current_pos.x + 1) * 4 + direction as usize)
     }
-    fn second((array, movements): Self::Parsed) -> Self::Output {
+    fn second((array, movements): &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         let (height, width) = array.dim();
 
         let sector_size = (height / 3).min(width / 3);
@@ -93,7 +106,7 @@ impl Day for Day22 {
         let mut position = (1, Pos::new(0, 0));
 
         let mut direction = Dir::Right;
-        for mov in movements {
+        for &mov in movements {
             match mov {
                 Movement::Turn(turn) => direction = direction + turn,
                 Movement::Num(num) => {
@@ -114,7 +127,7 @@ impl Day for Day22 {
         let row = pos[0] + 1;
         let col = pos[1] + 1;
         let fac = direction as usize;
-        row * 1000 + col * 4 + fac
+        Ok(row * 1000 + col * 4 + fac)
     }
 }
 
@@ -302,29 +315,15 @@ const fn cube_next_pos(pos: (usize, Pos), dir: Dir, sector_size: usize) -> ((usi
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "        ...#
-        .#..
-        #...
-        ....
-...#.......#
-........#...
-..#....#....
-..........#.
-        ...#....
-        .....#..
-        .#......
-        ......#.
-
-10R5L5R10L4R5L5";
     fn parsed() -> <Day22 as Day>::Parsed {
-        Day22::parse(INPUT.to_string()).unwrap()
+        Day22::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day22::first(parsed()), 6032);
+        assert_eq!(Day22::first(&parsed(), &()).unwrap(), 6032);
     }
     #[test]
     fn part2() {
-        //assert_eq!(Day22::second(parsed()), 0);
+        //assert_eq!(Day22::second(&parsed(), &()).unwrap(), 0);
     }
 }
@@ -0,0 +1,491 @@
+use super::day::{Day, Example};
+use crate::helpers::parse::lines;
+use anyhow::{Context, Result};
+use gif::{Encoder, Frame, Repeat};
+use itertools::Itertools;
+use ndarray::Array2;
+use std::{borrow::Cow, io::Write};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Point {
+    #[default]
+    Nothing,
+    Open,
+    Wall,
+    DrawRight,
+    DrawDown,
+    DrawLeft,
+    DrawUp,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Movement {
+    Num(u8),
+    Right,
+    Left,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Direction {
+    Right = 0,
+    Down = 1,
+    Left = 2,
+    Up = 3,
+}
+impl Direction {
+    const fn movement(&mut self, movement: Movement) {
+        use Direction::*;
+        match movement {
+            Movement::Num(_) => {}
+            Movement::Right => {
+                *self = match self {
+                    Up => Right,
+                    Right => Down,
+                    Down => Left,
+                    Left => Up,
+                }
+            }
+            Movement::Left => {
+                *self = match self {
+                    Up => Left,
+                    Right => Up,
+                    Down => Right,
+                    Left => Down,
+                }
+            }
+        }
+    }
+    fn turn_right(&mut self, times: usize) {
+        for _ in 0..(times % 4) {
+            self.movement(Movement::Right);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum IP {
+    Right = 0,
+    Bottom = 1,
+    Left = 2,
+    Top = 3,
+}
+
+type Animation<'a> = Encoder<&'a mut dyn Write>;
+
+/// Starts an animation of `map`, written into `out`.
+fn animation<'a>(out: &'a mut dyn Write, map: &Array2<Point>) -> Result<Animation<'a>> {
+    let (height, width) = map.dim();
+    // empty: fffcf2 - 0
+    // bg: ccc5b9 - 1
+    // rock: 403d39 - 2
+    // arrow: eb5e28 -3
+    let color_map = &[
+        0xFF, 0xFC, 0xF2, 0xCC, 0xC5, 0xB9, 0x40, 0x3d, 0x39, 0xEB, 0x5E, 0x28,
+    ];
+    let mut encoder = Encoder::new(
+        out,
+        u16::try_from(width * 3)?,
+        u16::try_from(height * 3)?,
+        color_map,
+    )?;
+    encoder.set_repeat(Repeat::Infinite)?;
+    Ok(encoder)
+}
+
+/// Draws every tile of `map` as three by three pixels.
+fn write_frame(encoder: &mut Animation<'_>, map: &Array2<Point>) -> Result<()> {
+    let (rows, cols) = map.dim();
+    let width = cols * 3;
+    let mut state = vec![0; width * rows * 3];
+    let mut frame = Frame::<'_> {
+        width: u16::try_from(width)?,
+        height: u16::try_from(rows * 3)?,
+        ..Default::default()
+    };
+    for y in 0..rows {
+        for x in 0..cols {
+            let point = map[[y, x]];
+            let draw = match point {
+                Point::Nothing => [[0; 3]; 3],
+                Point::Open => [[1; 3]; 3],
+                Point::Wall => [[2; 3]; 3],
+                Point::DrawRight => [[1, 3, 1], [1, 1, 3], [1, 3, 1]],
+                Point::DrawDown => [[1, 1, 1], [3, 1, 3], [1, 3, 1]],
+                Point::DrawLeft => [[1, 3, 1], [3, 1, 1], [1, 3, 1]],
+                Point::DrawUp => [[1, 3, 1], [3, 1, 3], [1, 1, 1]],
+            };
+            if point != Point::Nothing {
+                for dy in 0..3 {
+                    for dx in 0..3 {
+                        state[(y * 3 + dy) * width + x * 3 + dx] = draw[dy][dx];
+                    }
+                }
+            }
+        }
+    }
+    frame.buffer = Cow::Borrowed(&state);
+    encoder.write_frame(&frame)?;
+    Ok(())
+}
+
+const EXAMPLE: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5";
+
+pub struct Day22;
+impl Day for Day22 {
+    type Parsed = (Array2<Point>, Vec<Movement>);
+    type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("6032"), None])];
+
+    fn parse(input: String) -> Result<Self::Parsed> {
+        let mut input = lines(&input);
+        let map: Vec<_> = input.paragraph().collect();
+        let width = map.iter().map(|line| line.text().len()).max().unwrap_or(0);
+        let mut array = Array2::<Point>::default((map.len(), width));
+        for (y, line) in map.iter().enumerate() {
+            line.check_chars("' ', '.' or '#'", |c| matches!(c, ' ' | '.' | '#'))?;
+            for (x, c) in line.text().chars().enumerate() {
+                match c {
+                    '.' => array[[y, x]] = Point::Open,
+                    '#' => array[[y, x]] = Point::Wall,
+                    _ => {}
+                }
+            }
+        }
+        let mut path = input.expect_line("the path")?;
+        let mut movements = vec![];
+        while !path.is_empty() {
+            if path.rest().starts_with(|c: char| c.is_ascii_digit()) {
+                movements.push(Movement::Num(path.number()?));
+            } else {
+                movements.push(path.one_of(&[("L", Movement::Left), ("R", Movement::Right)])?);
+            }
+        }
+        Ok((array, movements))
+    }
+    fn first((array, movements): &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        let top_left_x = {
+            let row = array.row(0);
+            row.iter()
+                .find_position(|&&p| p == Point::Open)
+                .context("No open tile in the first row")?
+                .0
+        };
+        let mut current_pos = [0, top_left_x];
+        let mut direction = Direction::Right;
+        for &mov in movements {
+            direction.movement(mov);
+            if let Movement::Num(num) = mov {
+                for _ in 0..num {
+                    move_by(&mut current_pos, direction, array);
+                }
+            }
+        }
+        let row = current_pos[0] + 1;
+        let col = current_pos[1] + 1;
+        let fac = direction as usize;
+        Ok(row * 1000 + col * 4 + fac)
+    }
+    fn second((array, movements): &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        walk_cube(array, movements, None)
+    }
+    const DUMP: Option<&'static str> = Some("gif");
+    /// The walk on the cube of part 2, one frame per step.
+    fn dump(
+        (array, movements): &Self::Parsed,
+        _: &Self::Params,
+        out: &mut dyn Write,
+    ) -> Result<()> {
+        let mut encoder = animation(out, array)?;
+        walk_cube(array, movements, Some(&mut encoder))?;
+        encoder.into_inner()?;
+        Ok(())
+    }
+}
+
+/// Walks along the faces of the cube, drawing every step into `encoder`.
+#[allow(clippy::too_many_lines)]
+fn walk_cube(
+    array: &Array2<Point>,
+    movements: &[Movement],
+    encoder: Option<&mut Animation<'_>>,
+) -> Result<usize> {
+    let (height, width) = array.dim();
+
+    let mut animation = encoder.map(|encoder| (encoder, array.clone()));
+
+    let portals = [
+        // (right, down, left, up)
+        // ((index, (add right x times)))
+        [(0, IP::Left), (0, IP::Left), (0, IP::Left), (0, IP::Left)], // [0]
+        [(2, IP::Left), (4, IP::Top), (6, IP::Left), (9, IP::Left)],  // 1
+        [
+            (7, IP::Right),
+            (4, IP::Right),
+            (1, IP::Right),
+            (9, IP::Bottom),
+        ], // 2
+        [(0, IP::Left), (0, IP::Left), (0, IP::Left), (0, IP::Left)], // [3]
+        [(2, IP::Bottom), (7, IP::Top), (6, IP::Top), (1, IP::Bottom)], // 4
+        [(0, IP::Left), (0, IP::Left), (0, IP::Left), (0, IP::Left)], // [5]
+        [(7, IP::Left), (9, IP::Top), (1, IP::Left), (4, IP::Left)],  // 6
+        [
+            (2, IP::Right),
+            (9, IP::Right),
+            (6, IP::Right),
+            (4, IP::Bottom),
+        ], // 7
+        [(0, IP::Left), (0, IP::Left), (0, IP::Left), (0, IP::Left)], // [8]
+        [(7, IP::Bottom), (2, IP::Top), (1, IP::Top), (6, IP::Bottom)], // 9
+        [(0, IP::Left), (0, IP::Left), (0, IP::Left), (0, IP::Left)], // [10]
+        [(0, IP::Left), (0, IP::Left), (0, IP::Left), (0, IP::Left)], // [11]
+    ];
+    let sector_size = (height / 3).min(width / 3);
+    let sector_pos_to_pos = |pos: (usize, [usize; 2])| -> [usize; 2] {
+        let row = pos.0 / 3;
+        let col = pos.0 % 3;
+        [pos.1[0] + row * sector_size, pos.1[1] + col * sector_size]
+    };
+    let mut position = (1, [0, 0]);
+
+    let mut direction = Direction::Right;
+    for &mov in movements {
+        direction.movement(mov);
+        if let Movement::Num(num) = mov {
+            for _ in 0..num {
+                let mut try_position = position;
+                let mut try_direction = direction;
+                //println!("Current pos: {:?}", sector_pos_to_pos(current_pos));
+                match direction {
+                    Direction::Right => {
+                        if position.1[1] == sector_size - 1 {
+                            /* println!("Current pos: {current_pos:?}, direction: {direction:?}"); */
+                            let portal = portals[position.0][0];
+                            try_position.0 = portal.0;
+                            // Changing direction is [0]
+                            match portal.1 {
+                                IP::Right => {
+                                    try_position.1[0] = sector_size - position.1[0] - 1;
+                                    try_position.1[1] = sector_size - 1;
+                                }
+                                IP::Bottom => {
+                                    try_position.1[0] = sector_size - 1;
+                                    try_position.1[1] = position.1[0];
+                                }
+                                IP::Left => {
+                                    try_position.1[1] = 0;
+                                }
+                                IP::Top => {
+                                    // Untested direction. Might not work.
+                                    try_position.1[0] = 0;
+                                    try_position.1[1] = position.1[0];
+                                }
+                            }
+                            try_direction.turn_right(portal.1 as usize + 2);
+
+                            /* println!(
+                                "Going Right to {} with portal {portal:?}, checking position: {:?}, ",
+                                test_pos.0, test_pos.1
+                            ); */
+                        } else {
+                            try_position.1[1] += 1;
+                        }
+                    }
+                    Direction::Down => {
+                        if position.1[0] == sector_size - 1 {
+                            /* println!("Current pos: {current_pos:?}, direction: {direction:?}"); */
+                            let portal = portals[position.0][1];
+                            try_position.0 = portal.0;
+                            // Changing direction is [1]
+                            match portal.1 {
+                                IP::Right => {
+                                    try_position.1[0] = position.1[1];
+                                    try_position.1[1] = sector_size - 1;
+                                }
+                                IP::Bottom => {
+                                    // Untested direction. Might not work.
+                                    try_position.1[0] = sector_size - position.1[1] - 1;
+                                    try_position.1[1] = sector_size - 1;
+                                }
+                                IP::Left => {
+                                    // Untested direction. Might not work.
+                                    try_position.1[0] = position.1[1];
+                                    try_position.1[1] = 0;
+                                }
+                                IP::Top => {
+                                    try_position.1[0] = 0;
+                                }
+                            }
+                            try_direction.turn_right(portal.1 as usize + 1);
+                            /* println!(
+                                "Going Down to {} with portal {portal:?}, checking position: {:?}, ",
+                                test_pos.0, test_pos.1
+                            ); */
+                        } else {
+                            try_position.1[0] += 1;
+                        }
+                    }
+                    Direction::Left => {
+                        if position.1[1] == 0 {
+                            /* println!("Current pos: {current_pos:?}, direction: {direction:?}"); */
+                            let portal = portals[position.0][2];
+                            try_position.0 = portal.0;
+                            // Changing direction is [0]
+                            match portal.1 {
+                                IP::Right => {
+                                    try_position.1[1] = sector_size - 1;
+                                }
+                                IP::Bottom => {
+                                    try_position.1[0] = sector_size - 1;
+                                    try_position.1[1] = position.1[0];
+                                }
+                                IP::Left => {
+                                    try_position.1[0] = sector_size - position.1[0] - 1;
+                                    try_position.1[1] = 0;
+                                }
+                                IP::Top => {
+                                    try_position.1[0] = 0;
+                                    try_position.1[1] = position.1[0];
+                                }
+                            }
+                            try_direction.turn_right(portal.1 as usize);
+                            /* println!(
+                                "Going Left to {} with portal {portal:?}, checking position: {:?}, ",
+                                test_pos.0, test_pos.1
+                            ); */
+                        } else {
+                            try_position.1[1] -= 1;
+                        }
+                    }
+                    Direction::Up => {
+                        if position.1[0] == 0 {
+                            /* println!("Current pos: {current_pos:?}, direction: {direction:?}"); */
+                            let portal = portals[position.0][3];
+                            try_position.0 = portal.0;
+                            // Changing direction is [1]
+                            match portal.1 {
+                                IP::Right => {
+                                    // Untested direction. Might not work.
+                                    try_position.1[0] = sector_size - position.1[1] - 1;
+                                    try_position.1[1] = sector_size - 1;
+                                }
+                                IP::Bottom => {
+                                    try_position.1[0] = sector_size - 1;
+                                }
+                                IP::Left => {
+                                    try_position.1[0] = position.1[1];
+                                    try_position.1[1] = 0;
+                                }
+                                IP::Top => {
+                                    // Untested direction. Might not work.
+                                    try_position.1[0] = 0;
+                                }
+                            }
+                            try_direction.turn_right(portal.1 as usize + 3);
+                            /* println!(
+                                "Going Up to {} with portal {portal:?}, checking position: {:?}, ",
+                                test_pos.0, test_pos.1
+                            ); */
+                        } else {
+                            try_position.1[0] -= 1;
+                        }
+                    }
+                }
+                if try_position != position && array[sector_pos_to_pos(try_position)] == Point::Open
+                {
+                    if let Some((encoder, draw_array)) = &mut animation {
+                        draw_array[sector_pos_to_pos(position)] = match direction {
+                            Direction::Right => Point::DrawRight,
+                            Direction::Down => Point::DrawDown,
+                            Direction::Left => Point::DrawLeft,
+                            Direction::Up => Point::DrawUp,
+                        };
+                        write_frame(encoder, draw_array)?;
+                    }
+                    direction = try_direction;
+                    position = try_position;
+                }
+            }
+        }
+    }
+    let pos = sector_pos_to_pos(position);
+    let row = pos[0] + 1;
+    let col = pos[1] + 1;
+    let fac = direction as usize;
+    Ok(row * 1000 + col * 4 + fac)
+}
+
+fn move_by(current_pos: &mut [usize; 2], direction: Direction, array: &Array2<Point>) {
+    let (height, width) = array.dim();
+    let mut look_pos = *current_pos;
+    loop {
+        match direction {
+            Direction::Up => {
+                look_pos[0] = look_pos[0].wrapping_sub(1).min(height - 1);
+            }
+            Direction::Right => {
+                look_pos[1] = if look_pos[1] == width - 1 {
+                    0
+                } else {
+                    look_pos[1] + 1
+                };
+            }
+            Direction::Down => {
+                look_pos[0] = if look_pos[0] == height - 1 {
+                    0
+                } else {
+                    look_pos[0] + 1
+                };
+            }
+            Direction::Left => {
+                look_pos[1] = look_pos[1].wrapping_sub(1).min(width - 1);
+            }
+        }
+
+        match array[look_pos] {
+            Point::Open => {
+                *current_pos = look_pos;
+                break;
+            }
+            Point::Wall => {
+                break;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn parsed() -> <Day22 as Day>::Parsed {
+        Day22::parse(EXAMPLE.to_string()).unwrap()
+    }
+    #[test]
+    fn part1() {
+        assert_eq!(Day22::first(&parsed(), &()).unwrap(), 6032);
+    }
+    #[test]
+    fn part2() {
+        //assert_eq!(Day22::second(&parsed(), &()).unwrap(), 0);
+    }
+    #[test]
+    fn dump() {
+        let mut gif = vec![];
+        Day22::dump(&parsed(), &(), &mut gif).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+    }
+}
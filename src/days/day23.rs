@@ -1,52 +1,95 @@
 #![allow(clippy::cast_possible_wrap)]
-use super::day::Day;
+use super::day::{ConsumingDay, Example};
 use crate::helpers::grid2d::{Direction8Way, Position2D};
+use crate::helpers::parse::lines;
 use ahash::{HashMap, HashMapExt, HashSet};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use itertools::Itertools;
 use smallvec::SmallVec;
 
 type Pos = Position2D<isize>;
 type Dir = Direction8Way;
 
+const EXAMPLE: &str = "....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..";
+
 pub struct Day23;
-impl Day for Day23 {
+impl ConsumingDay for Day23 {
     type Parsed = HashSet<Pos>;
     type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("110"), Some("20")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.as_bytes()
-                    .iter()
+        let mut elves = HashSet::default();
+        for line in lines(&input) {
+            line.check_chars("'.' or '#'", |c| matches!(c, '.' | '#'))?;
+            let y = line.number - 1;
+            elves.extend(
+                line.text()
+                    .bytes()
                     .enumerate()
-                    .filter_map(move |(x, &c)| {
-                        (c == b'#').then(|| Pos::new(x as isize, y as isize))
-                    })
-            })
-            .collect())
+                    .filter(|&(_, c)| c == b'#')
+                    .map(|(x, _)| Pos::new(x as isize, y as isize)),
+            );
+        }
+        Ok(elves)
     }
-    fn first(mut map: Self::Parsed) -> Self::Output {
+    const SOLVES_BOTH: bool = true;
+
+    fn first(mut map: Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         let mut test_direction = 0;
         for _ in 0..10 {
             elf_round(&mut map, &mut test_direction);
         }
-        let xmm = map.iter().map(|k| k.x).minmax().into_option().unwrap();
-        let ymm = map.iter().map(|k| k.y).minmax().into_option().unwrap();
-        ((xmm.1 - xmm.0 + 1) * (ymm.1 - ymm.0 + 1)) as usize - map.len()
+        empty_ground(&map)
     }
-    fn second(mut map: Self::Parsed) -> Self::Output {
+    fn second(mut map: Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         let mut test_direction = 0;
         for round in 1.. {
             if elf_round(&mut map, &mut test_direction) == 0 {
-                return round;
+                return Ok(round);
+            }
+        }
+        Ok(0)
+    }
+    fn both(mut map: Self::Parsed, _: &Self::Params) -> Result<(Self::Output, Self::Output)> {
+        let mut test_direction = 0;
+        let mut empty = 0;
+        for round in 1.. {
+            let moved = elf_round(&mut map, &mut test_direction);
+            // Once the elves stop, later rounds don't change the ground.
+            if round == 10 || (moved == 0 && round < 10) {
+                empty = empty_ground(&map)?;
+            }
+            if moved == 0 {
+                return Ok((empty, round));
             }
         }
-        0
+        Ok((empty, 0))
     }
 }
+/// Empty tiles in the smallest rectangle around the elves.
+fn empty_ground(map: &HashSet<Pos>) -> Result<usize> {
+    let xmm = map
+        .iter()
+        .map(|k| k.x)
+        .minmax()
+        .into_option()
+        .context("No elves")?;
+    let ymm = map
+        .iter()
+        .map(|k| k.y)
+        .minmax()
+        .into_option()
+        .context("No elves")?;
+    Ok(((xmm.1 - xmm.0 + 1) * (ymm.1 - ymm.0 + 1)) as usize - map.len())
+}
 const PATHS: [[Dir; 3]; 4] = [
     [Dir::NW, Dir::N, Dir::NE],
     [Dir::SE, Dir::S, Dir::SW],
@@ -101,22 +144,19 @@ fn elf_round(map: &mut HashSet<Pos>, test_direction: &mut usize) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "....#..
-..###.#
-#...#.#
-.#...##
-#.###..
-##.#.##
-.#..#..";
-    fn parsed() -> <Day23 as Day>::Parsed {
-        Day23::parse(INPUT.to_string()).unwrap()
+    fn parsed() -> <Day23 as ConsumingDay>::Parsed {
+        Day23::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day23::first(parsed()), 110);
+        assert_eq!(Day23::first(parsed(), &()).unwrap(), 110);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day23::second(parsed()), 20);
+        assert_eq!(Day23::second(parsed(), &()).unwrap(), 20);
+    }
+    #[test]
+    fn both() {
+        assert_eq!(Day23::both(parsed(), &()).unwrap(), (110, 20));
     }
 }
@@ -1,10 +1,12 @@
-use super::day::Day;
+use super::day::{Day, Example};
 use crate::helpers::grid2d::{Direction4Way, Position2D};
+use crate::helpers::parse::lines;
 use ahash::{HashSet, HashSetExt};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use ndarray::Array2;
 use pathfinding::prelude::astar;
 use smallvec::{smallvec, SmallVec};
+use std::cell::RefCell;
 
 type Pos = Position2D<usize>;
 type Dir = Direction4Way;
@@ -20,22 +22,61 @@ impl Wind {
     }
 }
 
+/// The blizzards, simulated as far as the searches have needed them so far.
 #[derive(Debug, Clone)]
-pub struct Map {
+struct Blizzards {
     time: usize,
-    walls: HashSet<Pos>,
     winds: Vec<Wind>,
-    size: Pos,
+    /// Blocked tiles at every simulated time.
     cache: Vec<Array2<bool>>,
 }
+impl Blizzards {
+    fn simulate(&mut self, walls: &HashSet<Pos>, size: Pos) {
+        for wind in &mut self.winds {
+            wind.mov();
+            if walls.contains(&wind.pos) {
+                match wind.dir {
+                    Dir::Right => wind.pos.x = 1,
+                    Dir::Down => wind.pos.y = 1,
+                    Dir::Left => wind.pos.x = size.x - 2,
+                    Dir::Up => wind.pos.y = size.y - 2,
+                }
+            }
+        }
+        self.time += 1;
+        self.make_cache(walls, size);
+    }
+    fn make_cache(&mut self, walls: &HashSet<Pos>, size: Pos) {
+        if self.cache.len() == self.time {
+            let mut map = Array2::<bool>::default(size.xy());
+            for wall in walls {
+                map[wall.xy()] = true;
+            }
+            for wind in &self.winds {
+                map[wind.pos.xy()] = true;
+            }
+            self.cache.push(map);
+        }
+    }
+}
+
+/// The valley. The blizzards are simulated lazily behind a `RefCell`, so both
+/// parts share them instead of each simulating a copy.
+#[derive(Debug)]
+pub struct Map {
+    walls: HashSet<Pos>,
+    size: Pos,
+    blizzards: RefCell<Blizzards>,
+}
 impl Map {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self> {
         let mut walls = HashSet::new();
         let mut winds = Vec::new();
         let (mut maxx, mut maxy) = (0, 0);
-        for (y, line) in input.lines().enumerate() {
+        for (y, line) in lines(input).enumerate() {
+            line.check_chars("a wall, ground or wind", |c| "#.><v^".contains(c))?;
             maxy = maxy.max(y);
-            for (x, c) in line.chars().enumerate() {
+            for (x, c) in line.text().chars().enumerate() {
                 maxx = maxx.max(x);
                 let pos = Pos { x, y };
                 match c {
@@ -67,20 +108,23 @@ impl Map {
                 }
             }
         }
-        let mut s = Self {
+        let size = Pos::new(maxx + 1, maxy + 1);
+        let mut blizzards = Blizzards {
             time: 0,
-            walls,
             winds,
-            size: Pos::new(maxx + 1, maxy + 1),
             cache: vec![],
         };
-        s.make_cache();
-        s
+        blizzards.make_cache(&walls, size);
+        Ok(Self {
+            walls,
+            size,
+            blizzards: RefCell::new(blizzards),
+        })
     }
     fn _print_time(&self, time: usize) {
         for y in 0..self.size.y {
             for x in 0..self.size.y {
-                if self.cache[time][(x, y)] {
+                if self.blizzards.borrow().cache[time][(x, y)] {
                     print!("#");
                 } else {
                     print!(" ");
@@ -89,66 +133,56 @@ impl Map {
             println!();
         }
     }
-    fn is_blocked(&mut self, time: usize, pos: Pos) -> bool {
-        while self.time < time {
-            self.simulate();
-        }
-        *self.cache[time].get(pos.xy()).unwrap_or(&true)
-    }
-    fn simulate(&mut self) {
-        for wind in &mut self.winds {
-            wind.mov();
-            if self.walls.contains(&wind.pos) {
-                match wind.dir {
-                    Dir::Right => wind.pos.x = 1,
-                    Dir::Down => wind.pos.y = 1,
-                    Dir::Left => wind.pos.x = self.size.x - 2,
-                    Dir::Up => wind.pos.y = self.size.y - 2,
-                }
-            }
-        }
-        self.time += 1;
-        self.make_cache();
-    }
-    fn make_cache(&mut self) {
-        if self.cache.len() == self.time {
-            let mut map = Array2::<bool>::default(self.size.xy());
-            for wall in &self.walls {
-                map[wall.xy()] = true;
-            }
-            for wind in &self.winds {
-                map[wind.pos.xy()] = true;
-            }
-            self.cache.push(map);
+    fn is_blocked(&self, time: usize, pos: Pos) -> bool {
+        let mut blizzards = self.blizzards.borrow_mut();
+        while blizzards.time < time {
+            blizzards.simulate(&self.walls, self.size);
         }
+        *blizzards.cache[time].get(pos.xy()).unwrap_or(&true)
     }
 }
 
+const EXAMPLE: &str = "#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#";
+
 pub struct Day24;
 impl Day for Day24 {
     type Parsed = Map;
     type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("18"), Some("54")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(Map::new(&input))
+        Map::new(&input)
     }
-    fn first(mut map: Self::Parsed) -> Self::Output {
+    const SOLVES_BOTH: bool = true;
+
+    fn first(map: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         let end = map.size.sub_x(2).sub_y(1);
         let start = Pos::new(1, 0);
-        shortest_path(&mut map, start, end, 0)
+        shortest_path(map, start, end, 0)
     }
-    fn second(mut map: Self::Parsed) -> Self::Output {
+    fn second(map: &Self::Parsed, params: &Self::Params) -> Result<Self::Output> {
+        Ok(Self::both(map, params)?.1)
+    }
+    /// The first trip of part 2 is the answer of part 1.
+    fn both(map: &Self::Parsed, _: &Self::Params) -> Result<(Self::Output, Self::Output)> {
         let end = map.size.sub_x(2).sub_y(1);
         let start = Pos::new(1, 0);
-        let mut total_time = shortest_path(&mut map, start, end, 0);
-        total_time += shortest_path(&mut map, end, start, total_time);
-        total_time += shortest_path(&mut map, start, end, total_time);
-        total_time
+        let first_trip = shortest_path(map, start, end, 0)?;
+        let mut total_time = first_trip;
+        total_time += shortest_path(map, end, start, total_time)?;
+        total_time += shortest_path(map, start, end, total_time)?;
+        Ok((first_trip, total_time))
     }
 }
 
-fn shortest_path(map: &mut Map, start: Pos, end: Pos, start_time: usize) -> usize {
-    astar(
+fn shortest_path(map: &Map, start: Pos, end: Pos, start_time: usize) -> Result<usize> {
+    let (_, time) = astar(
         &(start_time, start),
         |(time, pos)| {
             let mut paths: SmallVec<[_; 5]> = smallvec![];
@@ -173,28 +207,26 @@ fn shortest_path(map: &mut Map, start: Pos, end: Pos, start_time: usize) -> usiz
         |(_, pos)| pos.manhattan(&end),
         |(_, pos)| *pos == end,
     )
-    .unwrap()
-    .1
+    .with_context(|| format!("No path from {start:?} to {end:?} through the blizzards"))?;
+    Ok(time)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "#.######
-#>>.<^<#
-#.<..<<#
-#>v.><>#
-#<^v^^>#
-######.#";
     fn parsed() -> <Day24 as Day>::Parsed {
-        Day24::parse(INPUT.to_string()).unwrap()
+        Day24::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day24::first(parsed()), 18);
+        assert_eq!(Day24::first(&parsed(), &()).unwrap(), 18);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day24::second(parsed()), 54);
+        assert_eq!(Day24::second(&parsed(), &()).unwrap(), 54);
+    }
+    #[test]
+    fn both() {
+        assert_eq!(Day24::both(&parsed(), &()).unwrap(), (18, 54));
     }
 }
@@ -1,5 +1,6 @@
 #![allow(clippy::cast_possible_wrap)]
-use super::day::Day;
+use super::day::{Day, Example};
+use crate::helpers::parse::lines;
 use anyhow::Result;
 
 const SNAFUS: &str = "=-012";
@@ -16,54 +17,76 @@ fn i_to_snafu(i: isize) -> String {
     if i == 0 {
         String::new()
     } else {
+        // Euclidean division keeps the digit in range for negative numbers.
+        let digit = (i + 2).rem_euclid(5) as usize;
         format!(
             "{}{}",
-            i_to_snafu((i + 2) / 5),
-            SNAFUS.chars().nth(((i + 2) % 5) as usize).unwrap()
+            i_to_snafu((i + 2).div_euclid(5)),
+            &SNAFUS[digit..=digit]
         )
     }
 }
+const EXAMPLE: &str = "1=-0-2
+12111
+2=0=
+21
+2=01
+111
+20012
+112
+1=-1=
+1-12
+12
+1=
+122";
+
 pub struct Day25;
 impl Day for Day25 {
     type Parsed = String;
     type Output = String;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("2=-1=0"), None])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
+        for line in lines(&input) {
+            line.check_chars("a SNAFU digit", |c| "=-012".contains(c))?;
+        }
         Ok(input)
     }
-    fn first(input: Self::Parsed) -> Self::Output {
+    fn first(input: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         let mut sum = 0;
         for line in input.lines() {
             sum += snafu_to_i(line);
         }
-        i_to_snafu(sum)
+        Ok(if sum == 0 {
+            "0".to_string()
+        } else {
+            i_to_snafu(sum)
+        })
     }
-    fn second(_lines: Self::Parsed) -> Self::Output {
-        "0".to_string()
+    fn second(_lines: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok("0".to_string())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "1=-0-2
-12111
-2=0=
-21
-2=01
-111
-20012
-112
-1=-1=
-1-12
-12
-1=
-122";
     fn parsed() -> <Day25 as Day>::Parsed {
-        Day25::parse(INPUT.to_string()).unwrap()
+        Day25::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day25::first(parsed()), "2=-1=0");
+        assert_eq!(Day25::first(&parsed(), &()).unwrap(), "2=-1=0");
+    }
+    #[test]
+    fn negative_and_zero_sums() {
+        let first = |input: &str| Day25::first(&input.to_string(), &()).unwrap();
+        assert_eq!(first("-\n="), "-2");
+        assert_eq!(first("=\n-\n"), "-2");
+        assert_eq!(first("1=\n-2"), "0");
+        for i in -200..200 {
+            assert_eq!(snafu_to_i(&i_to_snafu(i)), i);
+        }
     }
 }
@@ -1,4 +1,5 @@
-use super::day::Day;
+use super::day::{Day, Example};
+use crate::helpers::parse::lines;
 use anyhow::Result;
 use itertools::Itertools;
 
@@ -10,16 +11,31 @@ const fn char_as_score(c: char) -> i32 {
     }
 }
 
+const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+
 pub struct Day3;
 impl Day for Day3 {
     type Parsed = String;
     type Output = i32;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("157"), Some("70")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
+        for line in lines(&input) {
+            line.check_chars("a letter", |c| c.is_ascii_alphabetic())?;
+            if line.text().len() % 2 != 0 {
+                Err(line.error_at(0, line.text().len(), "an even number of items"))?;
+            }
+        }
         Ok(input)
     }
-    fn first(lines: Self::Parsed) -> Self::Output {
-        lines
+    fn first(lines: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(lines
             .lines()
             .map(|line| {
                 let (left, right) = line.split_at(line.len() / 2);
@@ -27,10 +43,10 @@ impl Day for Day3 {
                     .find_map(|c| right.find(c).map(|_| char_as_score(c)))
                     .unwrap_or(0)
             })
-            .sum()
+            .sum())
     }
-    fn second(lines: Self::Parsed) -> Self::Output {
-        lines
+    fn second(lines: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(lines
             .lines()
             .tuples()
             .filter_map(|(a, b, c)| {
@@ -40,28 +56,22 @@ impl Day for Day3 {
                         .map(|_| char_as_score(char))
                 })
             })
-            .sum()
+            .sum())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
     fn parsed() -> <Day3 as Day>::Parsed {
-        Day3::parse(INPUT.to_string()).unwrap()
+        Day3::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day3::first(parsed()), 157);
+        assert_eq!(Day3::first(&parsed(), &()).unwrap(), 157);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day3::second(parsed()), 70);
+        assert_eq!(Day3::second(&parsed(), &()).unwrap(), 70);
     }
 }
@@ -1,7 +1,7 @@
-use super::day::Day;
+use super::day::{ConsumingDay, Example};
+use crate::helpers::parse::{lines, ParseError};
 use crate::helpers::BorrowTwo;
 use anyhow::Result;
-use itertools::Itertools;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Move {
@@ -38,82 +38,87 @@ impl Stacks {
     }
 }
 
+const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
 pub struct Day5;
-impl Day for Day5 {
+impl ConsumingDay for Day5 {
     type Parsed = (Stacks, Vec<Move>);
     type Output = String;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("CMZ"), Some("MCD")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        let (input_stacks, input_moves) = input.split_once("\n\n").unwrap();
-
-        let num_stacks = (input_stacks.lines().next().unwrap().len() + 1) / 4;
+        let mut input = lines(&input);
+        let drawing: Vec<_> = input.paragraph().collect();
+        let (numbers, crates) = drawing
+            .split_last()
+            .ok_or_else(|| input.error("a drawing of the stacks"))?;
+        let num_stacks = (numbers.text().len() + 1) / 4;
         let mut stacks = vec![Vec::new(); num_stacks];
 
-        for line in input_stacks.lines() {
-            if line.chars().nth(1).unwrap() == '1' {
-                break;
-            }
+        for line in crates {
             for (i, stack) in stacks.iter_mut().enumerate() {
-                let letter = line.chars().nth(i * 4 + 1).unwrap();
-                if letter >= 'A' {
-                    stack.insert(0, letter);
+                match line.text().as_bytes().get(i * 4 + 1) {
+                    Some(&letter) if letter.is_ascii_uppercase() => stack.insert(0, letter as char),
+                    Some(b' ') | None => {}
+                    Some(_) => Err(line.error_at(i * 4 + 1, 1, "a crate letter"))?,
                 }
             }
         }
 
-        let moves = input_moves
-            .lines()
-            .map(|line| {
-                let m: (usize, usize, usize) = line
-                    .split(' ')
-                    .filter_map(|s| s.parse().ok())
-                    .next_tuple()
-                    .unwrap();
-                Move {
-                    num: m.0,
-                    from: m.1 - 1,
-                    to: m.2 - 1,
-                }
+        let moves = input
+            .map(|mut line| {
+                line.literal("move ")?;
+                let num = line.number()?;
+                line.literal(" from ")?;
+                let from = line.number_in(1..=num_stacks)?;
+                line.literal(" to ")?;
+                let to = line.number_in(1..=num_stacks)?;
+                line.end()?;
+                Ok(Move {
+                    num,
+                    from: from - 1,
+                    to: to - 1,
+                })
             })
-            .collect();
+            .collect::<Result<_, ParseError>>()?;
 
         Ok((Stacks(stacks), moves))
     }
-    fn first((mut stacks, moves): Self::Parsed) -> Self::Output {
+    fn first((mut stacks, moves): Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         for mov in moves {
             stacks.move_many_one_by_one(mov);
         }
-        stacks.get_top()
+        Ok(stacks.get_top())
     }
-    fn second((mut stacks, moves): Self::Parsed) -> Self::Output {
+    fn second((mut stacks, moves): Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         for mov in moves {
             stacks.move_many(mov);
         }
-        stacks.get_top()
+        Ok(stacks.get_top())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2";
-    fn parsed() -> <Day5 as Day>::Parsed {
-        Day5::parse(INPUT.to_string()).unwrap()
+    fn parsed() -> <Day5 as ConsumingDay>::Parsed {
+        Day5::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day5::first(parsed()), "CMZ");
+        assert_eq!(Day5::first(parsed(), &()).unwrap(), "CMZ");
     }
     #[test]
     fn part2() {
-        assert_eq!(Day5::second(parsed()), "MCD");
+        assert_eq!(Day5::second(parsed(), &()).unwrap(), "MCD");
     }
 }
@@ -1,8 +1,9 @@
-use super::day::Day;
+use super::day::{Day, Example};
+use crate::helpers::parse::lines;
 use crate::helpers::BitArray;
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-fn find_first_unique<const UNIQUE: usize>(chars: &[u8]) -> usize {
+fn find_first_unique<const UNIQUE: usize>(chars: &[u8]) -> Result<usize> {
     let a = chars
         .windows(UNIQUE)
         .enumerate()
@@ -17,50 +18,72 @@ fn find_first_unique<const UNIQUE: usize>(chars: &[u8]) -> usize {
                 None
             }
         })
-        .unwrap();
-    a + UNIQUE
+        .with_context(|| format!("No {UNIQUE} different characters in a row"))?;
+    Ok(a + UNIQUE)
 }
 
 pub struct Day6;
 impl Day for Day6 {
     type Parsed = Vec<u8>;
     type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            name: "example1",
+            ..Example::new("mjqjpqmgbljsphdztnvjfqwrcgsmlb", [Some("7"), Some("19")])
+        },
+        Example {
+            name: "example2",
+            ..Example::new("bvwbjplbgvbhsrlpgdmjqwftvncz", [Some("5"), Some("23")])
+        },
+        Example {
+            name: "example3",
+            ..Example::new("nppdvjthqldpwncqszvftbrmjlhg", [Some("6"), Some("23")])
+        },
+        Example {
+            name: "example4",
+            ..Example::new(
+                "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg",
+                [Some("10"), Some("29")],
+            )
+        },
+        Example {
+            name: "example5",
+            ..Example::new("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", [Some("11"), Some("26")])
+        },
+    ];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input.trim_end().as_bytes().to_vec())
+        let line = lines(&input).expect_line("a signal")?;
+        line.check_chars("a lowercase letter", |c| c.is_ascii_lowercase())?;
+        Ok(line.text().as_bytes().to_vec())
     }
-    fn first(chars: Self::Parsed) -> Self::Output {
-        find_first_unique::<4>(&chars)
+    fn first(chars: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        find_first_unique::<4>(chars)
     }
-    fn second(chars: Self::Parsed) -> Self::Output {
-        find_first_unique::<14>(&chars)
+    fn second(chars: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        find_first_unique::<14>(chars)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const TESTS: [(&str, usize, usize); 5] = [
-        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
-        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
-        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
-        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
-        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
-    ];
-
     fn parse(input: &str) -> <Day6 as Day>::Parsed {
         Day6::parse(input.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        for i in TESTS {
-            assert_eq!(Day6::first(parse(i.0)), i.1);
+        for example in Day6::EXAMPLES {
+            let answer = Day6::first(&parse(example.input), &()).unwrap();
+            assert_eq!(Some(&answer.to_string()[..]), example.answers[0]);
         }
     }
     #[test]
     fn part2() {
-        for i in TESTS {
-            assert_eq!(Day6::second(parse(i.0)), i.2);
+        for example in Day6::EXAMPLES {
+            let answer = Day6::second(&parse(example.input), &()).unwrap();
+            assert_eq!(Some(&answer.to_string()[..]), example.answers[1]);
         }
     }
 }
@@ -1,87 +1,98 @@
-use super::day::Day;
-use anyhow::Result;
+use super::day::{Day, Example};
+use crate::helpers::parse::lines;
+use anyhow::{Context, Result};
 use std::collections::HashMap;
 
+const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
 pub struct Day7;
 impl Day for Day7 {
     type Parsed = HashMap<Vec<String>, i32>;
     type Output = i32;
+    type Params = ();
+    const EXAMPLES: &'static [Example] =
+        &[Example::new(EXAMPLE, [Some("95437"), Some("24933642")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
         let mut folders = HashMap::new();
         let mut current_folders: Vec<String> = vec![];
-        for line in input.lines() {
-            if let Some(folder) = line.strip_prefix("$ cd ") {
+        for mut line in lines(&input) {
+            if let Some(folder) = line.text().strip_prefix("$ cd ") {
                 if folder == ".." {
                     current_folders.pop();
                 } else {
                     current_folders.push(folder.to_string());
                     folders.insert(current_folders.clone(), 0);
                 }
-            } else if line.chars().next().unwrap().is_numeric() {
+            } else if line.text().starts_with(|c: char| c.is_ascii_digit()) {
+                let size = line.number::<i32>()?;
+                line.literal(" ")?;
                 let mut own = current_folders.clone();
                 while !own.is_empty() {
-                    *folders.get_mut(&own).unwrap() +=
-                        line.split_once(' ').unwrap().0.parse::<i32>()?;
+                    *folders.get_mut(&own).unwrap() += size;
                     own.pop();
                 }
+            } else if line.text() != "$ ls" && !line.text().starts_with("dir ") {
+                Err(line.error(r#""$ cd", "$ ls", "dir" or a file size"#))?;
             }
         }
         Ok(folders)
     }
-    fn first(folders: Self::Parsed) -> Self::Output {
-        folders
+    fn first(folders: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(folders
             .iter()
             .map(|(_, &size)| size)
             .filter(|&size| size <= 100_000)
-            .sum::<i32>()
+            .sum::<i32>())
     }
-    fn second(folders: Self::Parsed) -> Self::Output {
-        let min_to_delete = folders.get(&vec!["/".to_string()]).unwrap() - 40_000_000;
+    fn second(folders: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        let min_to_delete = folders
+            .get(&vec!["/".to_string()])
+            .context("No root folder")?
+            - 40_000_000;
         folders
             .iter()
             .map(|(_, &size)| size)
             .filter(|&size| size > min_to_delete)
             .min()
-            .unwrap()
+            .context("No folder is big enough to free the space")
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k";
     fn parsed() -> <Day7 as Day>::Parsed {
-        Day7::parse(INPUT.to_string()).unwrap()
+        Day7::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day7::first(parsed()), 95437);
+        assert_eq!(Day7::first(&parsed(), &()).unwrap(), 95437);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day7::second(parsed()), 24933642);
+        assert_eq!(Day7::second(&parsed(), &()).unwrap(), 24_933_642);
     }
 }
@@ -1,5 +1,6 @@
-use super::day::Day;
-use anyhow::Result;
+use super::day::{Day, Example};
+use crate::helpers::parse::{lines, ParseError};
+use anyhow::{Context, Result};
 
 enum Direction {
     Up,
@@ -87,75 +88,77 @@ fn count_visible(val: u8, map: &Vec<Vec<u8>>, direction: Direction, x: usize, y:
     }
 }
 
+const EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390";
+
 pub struct Day8;
 impl Day for Day8 {
     type Parsed = Vec<Vec<u8>>;
     type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[Example::new(EXAMPLE, [Some("21"), Some("8")])];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .lines()
+        Ok(lines(&input)
             .map(|line| {
-                line.chars()
-                    .map(|i| i.to_digit(10).unwrap().try_into().unwrap())
-                    .collect()
+                line.check_chars("a digit", |c| c.is_ascii_digit())?;
+                Ok(line.text().bytes().map(|c| c - b'0').collect())
             })
-            .collect())
+            .collect::<Result<_, ParseError>>()?)
     }
-    fn first(map: Self::Parsed) -> Self::Output {
-        map.iter()
+    fn first(map: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(map
+            .iter()
             .enumerate()
             .map(|(y, line)| {
                 line.iter()
                     .enumerate()
                     .map(|(x, &item)| {
                         usize::from(
-                            check_visible(item, &map, Direction::Up, x, y)
-                                || check_visible(item, &map, Direction::Right, x, y)
-                                || check_visible(item, &map, Direction::Down, x, y)
-                                || check_visible(item, &map, Direction::Left, x, y),
+                            check_visible(item, map, Direction::Up, x, y)
+                                || check_visible(item, map, Direction::Right, x, y)
+                                || check_visible(item, map, Direction::Down, x, y)
+                                || check_visible(item, map, Direction::Left, x, y),
                         )
                     })
                     .sum::<usize>()
             })
-            .sum()
+            .sum())
     }
-    fn second(map: Self::Parsed) -> Self::Output {
+    fn second(map: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
         map.iter()
             .enumerate()
             .filter_map(|(y, line)| {
                 line.iter()
                     .enumerate()
                     .map(|(x, &item)| {
-                        count_visible(item, &map, Direction::Up, x, y)
-                            * count_visible(item, &map, Direction::Right, x, y)
-                            * count_visible(item, &map, Direction::Down, x, y)
-                            * count_visible(item, &map, Direction::Left, x, y)
+                        count_visible(item, map, Direction::Up, x, y)
+                            * count_visible(item, map, Direction::Right, x, y)
+                            * count_visible(item, map, Direction::Down, x, y)
+                            * count_visible(item, map, Direction::Left, x, y)
                     })
                     .max()
             })
             .max()
-            .unwrap()
+            .context("No trees")
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = "30373
-25512
-65332
-33549
-35390";
     fn parsed() -> <Day8 as Day>::Parsed {
-        Day8::parse(INPUT.to_string()).unwrap()
+        Day8::parse(EXAMPLE.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day8::first(parsed()), 21);
+        assert_eq!(Day8::first(&parsed(), &()).unwrap(), 21);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day8::second(parsed()), 8);
+        assert_eq!(Day8::second(&parsed(), &()).unwrap(), 8);
     }
 }
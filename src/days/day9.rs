@@ -1,4 +1,5 @@
-use super::day::Day;
+use super::day::{Day, Example};
+use crate::helpers::parse::{lines, ParseError};
 use ahash::HashSetExt;
 use anyhow::Result;
 use rustc_hash::FxHashSet;
@@ -33,31 +34,57 @@ impl Move {
     }
 }
 
+const EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2";
+const LARGER_EXAMPLE: &str = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20";
+
 pub struct Day9;
 impl Day for Day9 {
     type Parsed = Vec<Move>;
     type Output = usize;
+    type Params = ();
+    const EXAMPLES: &'static [Example] = &[
+        Example::new(EXAMPLE, [Some("13"), Some("1")]),
+        Example {
+            name: "larger",
+            ..Example::new(LARGER_EXAMPLE, [Some("88"), Some("36")])
+        },
+    ];
 
     fn parse(input: String) -> Result<Self::Parsed> {
-        Ok(input
-            .lines()
-            .map(|line| Move {
-                direction: match &line[0..1] {
-                    "U" => Direction::Up,
-                    "R" => Direction::Right,
-                    "D" => Direction::Down,
-                    "L" => Direction::Left,
-                    _ => panic!(),
-                },
-                num_moves: line[2..].parse::<i32>().unwrap(),
+        use Direction::*;
+        Ok(lines(&input)
+            .map(|mut line| {
+                let direction =
+                    line.one_of(&[("U", Up), ("R", Right), ("D", Down), ("L", Left)])?;
+                line.literal(" ")?;
+                let num_moves = line.number()?;
+                line.end()?;
+                Ok(Move {
+                    direction,
+                    num_moves,
+                })
             })
-            .collect())
+            .collect::<Result<_, ParseError>>()?)
     }
-    fn first(moves: Self::Parsed) -> Self::Output {
-        snake_move::<2>(&moves)
+    fn first(moves: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(snake_move::<2>(moves))
     }
-    fn second(moves: Self::Parsed) -> Self::Output {
-        snake_move::<10>(&moves)
+    fn second(moves: &Self::Parsed, _: &Self::Params) -> Result<Self::Output> {
+        Ok(snake_move::<10>(moves))
     }
 }
 
@@ -86,33 +113,17 @@ fn snake_move<const SNAKE_LENGTH: usize>(moves: &Vec<Move>) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT1: &str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2";
-    const INPUT2: &str = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20";
     fn parsed(input: &str) -> <Day9 as Day>::Parsed {
         Day9::parse(input.to_string()).unwrap()
     }
     #[test]
     fn part1() {
-        assert_eq!(Day9::first(parsed(INPUT1)), 13);
-        assert_eq!(Day9::first(parsed(INPUT2)), 88);
+        assert_eq!(Day9::first(&parsed(EXAMPLE), &()).unwrap(), 13);
+        assert_eq!(Day9::first(&parsed(LARGER_EXAMPLE), &()).unwrap(), 88);
     }
     #[test]
     fn part2() {
-        assert_eq!(Day9::second(parsed(INPUT1)), 1);
-        assert_eq!(Day9::second(parsed(INPUT2)), 36);
+        assert_eq!(Day9::second(&parsed(EXAMPLE), &()).unwrap(), 1);
+        assert_eq!(Day9::second(&parsed(LARGER_EXAMPLE), &()).unwrap(), 36);
     }
 }
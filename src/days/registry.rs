@@ -0,0 +1,350 @@
+use super::{
+    day::{ConsumingDay, Day, Example, Params as _},
+    *,
+};
+use crate::answer::Answer;
+use anyhow::{Context, Result};
+use std::{
+    any::Any,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// Parsed input of some day, as returned by [`Runner::parse`].
+pub type Parsed = Box<dyn Any>;
+/// Puzzle parameters of some day, as returned by [`Runner::params`].
+pub type Params = Box<dyn Any>;
+
+type BothFn = fn(PartInput, &Params) -> Result<[Answer; 2]>;
+type DumpFn = fn(&Parsed, &Params, &mut dyn Write) -> Result<()>;
+
+/// What a part runs on, see [`Runner::part_input`].
+pub enum PartInput<'a> {
+    Borrowed(&'a Parsed),
+    /// A copy of the parsed input, for days whose parts consume it.
+    Owned(Parsed),
+}
+impl PartInput<'_> {
+    fn get<T: 'static>(&self) -> &T {
+        match self {
+            Self::Borrowed(parsed) => downcast(parsed),
+            Self::Owned(parsed) => downcast(parsed),
+        }
+    }
+    fn into_owned<T: Clone + 'static>(self) -> T {
+        match self {
+            Self::Borrowed(parsed) => downcast::<T>(parsed).clone(),
+            Self::Owned(parsed) => *parsed.downcast().expect("Value of another day"),
+        }
+    }
+}
+
+/// A type-erased [`Day`] or [`ConsumingDay`] implementation.
+pub struct Runner {
+    pub day: u8,
+    pub name: &'static str,
+    pub variant: &'static str,
+    pub examples: &'static [Example],
+    parse: fn(String) -> Result<Parsed>,
+    params: fn(&[(String, String)]) -> Result<Params>,
+    default_params: fn() -> Vec<(&'static str, String)>,
+    /// Copies the parsed input, only for days whose parts consume it.
+    clone: Option<fn(&Parsed) -> Parsed>,
+    parts: [fn(PartInput, &Params) -> Result<Answer>; 2],
+    /// Solves both parts together, only for days that share work between them.
+    both: Option<BothFn>,
+    /// The extension of the file a day dumps and how it writes it.
+    dump: Option<(&'static str, DumpFn)>,
+}
+impl Runner {
+    pub const fn new<T: Day + 'static>(day: u8, name: &'static str, variant: &'static str) -> Self {
+        Self {
+            day,
+            name,
+            variant,
+            examples: T::EXAMPLES,
+            parse: |input| Ok(Box::new(T::parse(input)?)),
+            params: params::<T::Params>,
+            default_params: || T::Params::default().values(),
+            clone: None,
+            parts: [
+                |input, params| Ok(T::first(input.get(), downcast(params))?.into()),
+                |input, params| Ok(T::second(input.get(), downcast(params))?.into()),
+            ],
+            both: if T::SOLVES_BOTH {
+                Some(|input, params| {
+                    let (first, second) = T::both(input.get(), downcast(params))?;
+                    Ok([first.into(), second.into()])
+                })
+            } else {
+                None
+            },
+            dump: match T::DUMP {
+                Some(extension) => Some((extension, |parsed, params, out| {
+                    T::dump(downcast(parsed), downcast(params), out)
+                })),
+                None => None,
+            },
+        }
+    }
+    pub const fn consuming<T: ConsumingDay + 'static>(
+        day: u8,
+        name: &'static str,
+        variant: &'static str,
+    ) -> Self {
+        Self {
+            day,
+            name,
+            variant,
+            examples: T::EXAMPLES,
+            parse: |input| Ok(Box::new(T::parse(input)?)),
+            params: params::<T::Params>,
+            default_params: || T::Params::default().values(),
+            clone: Some(|parsed| Box::new(downcast::<T::Parsed>(parsed).clone())),
+            parts: [
+                |input, params| Ok(T::first(input.into_owned(), downcast(params))?.into()),
+                |input, params| Ok(T::second(input.into_owned(), downcast(params))?.into()),
+            ],
+            both: if T::SOLVES_BOTH {
+                Some(|input, params| {
+                    let (first, second) = T::both(input.into_owned(), downcast(params))?;
+                    Ok([first.into(), second.into()])
+                })
+            } else {
+                None
+            },
+            dump: match T::DUMP {
+                Some(extension) => Some((extension, |parsed, params, out| {
+                    T::dump(downcast(parsed), downcast(params), out)
+                })),
+                None => None,
+            },
+        }
+    }
+    pub fn parse(&self, input: String) -> Result<Parsed> {
+        (self.parse)(input)
+    }
+    /// The puzzle parameters, with the given ones changed by name.
+    pub fn params(&self, changes: &[(String, String)]) -> Result<Params> {
+        (self.params)(changes)
+    }
+    /// Every puzzle parameter by name, with its default value.
+    pub fn default_params(&self) -> Vec<(&'static str, String)> {
+        (self.default_params)()
+    }
+    /// Whether every part needs a copy of the parsed input.
+    pub const fn consumes(&self) -> bool {
+        self.clone.is_some()
+    }
+    /// The input of a part, which is a copy of the parsed input when the
+    /// part consumes it.
+    pub fn part_input<'a>(&self, parsed: &'a Parsed) -> PartInput<'a> {
+        match self.clone {
+            Some(clone) => PartInput::Owned(clone(parsed)),
+            None => PartInput::Borrowed(parsed),
+        }
+    }
+    /// Runs part 1 or 2 for `part` 0 or 1.
+    pub fn part(&self, part: usize, input: PartInput, params: &Params) -> Result<Answer> {
+        self.parts[part](input, params)
+    }
+    /// Whether the day shares work between the parts, see [`Runner::both`].
+    pub const fn solves_both(&self) -> bool {
+        self.both.is_some()
+    }
+    /// Runs both parts together. Only for days that [solve both](Runner::solves_both).
+    pub fn both(&self, input: PartInput, params: &Params) -> Result<[Answer; 2]> {
+        let both = self.both.expect("Day doesn't solve both parts together");
+        both(input, params)
+    }
+    /// Writes what solving `parsed` looks like into a file in `dir` named
+    /// after the runner and the input, for days that can show it. Returns
+    /// the file, `None` for other days.
+    pub fn dump(
+        &self,
+        parsed: &Parsed,
+        params: &Params,
+        dir: &Path,
+        input: &str,
+    ) -> Result<Option<PathBuf>> {
+        let Some((extension, dump)) = self.dump else {
+            return Ok(None);
+        };
+        fs::create_dir_all(dir).with_context(|| format!("Can't create {}", dir.display()))?;
+        let path = dir.join(format!("{}_{input}.{extension}", self.name));
+        let file =
+            File::create(&path).with_context(|| format!("Can't create {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        dump(parsed, params, &mut out)?;
+        out.flush()
+            .with_context(|| format!("Can't write {}", path.display()))?;
+        Ok(Some(path))
+    }
+}
+
+fn params<T: super::day::Params>(changes: &[(String, String)]) -> Result<Params> {
+    let mut params = T::default();
+    for (name, value) in changes {
+        params.set(name, value)?;
+    }
+    Ok(Box::new(params))
+}
+
+/// The parsed input or parameters of a day, which has to be the one `T` is of.
+fn downcast<T: 'static>(value: &Box<dyn Any>) -> &T {
+    value.downcast_ref().expect("Value of another day")
+}
+
+pub const DEFAULT_VARIANT: &str = "default";
+
+pub static RUNNERS: &[Runner] = &[
+    Runner::new::<day1::Day1>(1, "day1", DEFAULT_VARIANT),
+    Runner::new::<day2::Day2>(2, "day2", DEFAULT_VARIANT),
+    Runner::new::<day3::Day3>(3, "day3", DEFAULT_VARIANT),
+    Runner::new::<day4::Day4>(4, "day4", DEFAULT_VARIANT),
+    Runner::consuming::<day5::Day5>(5, "day5", DEFAULT_VARIANT),
+    Runner::new::<day6::Day6>(6, "day6", DEFAULT_VARIANT),
+    Runner::new::<day7::Day7>(7, "day7", DEFAULT_VARIANT),
+    Runner::new::<day8::Day8>(8, "day8", DEFAULT_VARIANT),
+    Runner::new::<day9::Day9>(9, "day9", DEFAULT_VARIANT),
+    Runner::new::<day10::Day10>(10, "day10", DEFAULT_VARIANT),
+    Runner::consuming::<day11::Day11>(11, "day11", DEFAULT_VARIANT),
+    Runner::consuming::<day11_queue::Day11>(11, "day11_queue", "queue"),
+    Runner::new::<day12::Day12>(12, "day12", DEFAULT_VARIANT),
+    Runner::consuming::<day13::Day13>(13, "day13", DEFAULT_VARIANT),
+    Runner::new::<day14::Day14>(14, "day14", DEFAULT_VARIANT),
+    Runner::consuming::<day14_gif::Day14>(14, "day14_gif", "gif"),
+    Runner::new::<day15::Day15>(15, "day15", DEFAULT_VARIANT),
+    Runner::new::<day16::Day16>(16, "day16", DEFAULT_VARIANT),
+    Runner::new::<day17::Day17>(17, "day17", DEFAULT_VARIANT),
+    Runner::new::<day18::Day18>(18, "day18", DEFAULT_VARIANT),
+    Runner::new::<day19::Day19>(19, "day19", DEFAULT_VARIANT),
+    Runner::consuming::<day20::Day20>(20, "day20", DEFAULT_VARIANT),
+    Runner::new::<day21::Day21>(21, "day21", DEFAULT_VARIANT),
+    Runner::new::<day22::Day22>(22, "day22", DEFAULT_VARIANT),
+    Runner::new::<day22_gif::Day22>(22, "day22_gif", "gif"),
+    Runner::consuming::<day23::Day23>(23, "day23", DEFAULT_VARIANT),
+    Runner::new::<day24::Day24>(24, "day24", DEFAULT_VARIANT),
+    Runner::new::<day25::Day25>(25, "day25", DEFAULT_VARIANT),
+];
+
+/// The runners of a day with one of the given variants, `all` selecting
+/// every variant. Without any variants, only the default one is selected.
+pub fn select(day: u8, variants: &[String]) -> Vec<&'static Runner> {
+    RUNNERS
+        .iter()
+        .filter(|runner| runner.day == day)
+        .filter(|runner| {
+            if variants.is_empty() {
+                runner.variant == DEFAULT_VARIANT
+            } else {
+                variants.iter().any(|v| v == "all" || v == runner.variant)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn every_day_has_a_default() {
+        for day in 1..=25 {
+            let runners = select(day, &[]);
+            assert_eq!(runners.len(), 1);
+            assert_eq!(runners[0].day, day);
+        }
+        let variants = |names: &[&str]| {
+            let names: Vec<_> = names.iter().copied().map(String::from).collect();
+            select(11, &names)
+                .iter()
+                .map(|r| r.variant)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(variants(&["queue"]), ["queue"]);
+        assert_eq!(variants(&["all"]), ["default", "queue"]);
+        assert!(variants(&["gif"]).is_empty());
+    }
+    #[test]
+    fn examples() {
+        for runner in RUNNERS {
+            assert!(
+                !runner.examples.is_empty(),
+                "{} has no example",
+                runner.name
+            );
+            for example in runner.examples {
+                let parsed = runner.parse(example.input.to_string()).unwrap();
+                let changes: Vec<_> = example
+                    .params
+                    .iter()
+                    .map(|&(name, value)| (name.to_string(), value.to_string()))
+                    .collect();
+                let params = runner.params(&changes).unwrap();
+                for (part, expected) in example.answers.iter().enumerate() {
+                    let Some(expected) = expected else {
+                        continue;
+                    };
+                    let answer = runner
+                        .part(part, runner.part_input(&parsed), &params)
+                        .unwrap();
+                    let expected = Answer::Text((*expected).to_string());
+                    assert!(
+                        answer.matches(&expected),
+                        "{} {}",
+                        runner.name,
+                        example.name
+                    );
+                }
+            }
+        }
+    }
+    #[test]
+    fn erased_parts() {
+        let runner = select(1, &[])[0];
+        let parsed = runner.parse("1\n2\n\n4".to_string()).unwrap();
+        let params = runner.params(&[]).unwrap();
+        assert!(!runner.consumes());
+        let part = |i| {
+            runner
+                .part(i, runner.part_input(&parsed), &params)
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!((part(0), part(1)), ("4".to_string(), "7".to_string()));
+        let runner = select(20, &[])[0];
+        let parsed = runner.parse("1\n2\n-3\n3\n-2\n0\n4".to_string()).unwrap();
+        let params = runner.params(&[]).unwrap();
+        assert!(runner.consumes());
+        let part = |i| {
+            runner
+                .part(i, runner.part_input(&parsed), &params)
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!((part(0), part(0)), ("3".to_string(), "3".to_string()));
+    }
+    #[test]
+    fn params() {
+        let runner = select(20, &[])[0];
+        assert_eq!(
+            runner.default_params(),
+            [
+                ("key", "811589153".to_string()),
+                ("rounds", "10".to_string())
+            ]
+        );
+        let parsed = runner.parse("1\n2\n-3\n3\n-2\n0\n4".to_string()).unwrap();
+        let change =
+            |name: &str, value: &str| runner.params(&[(name.to_string(), value.to_string())]);
+        let params = change("rounds", "1").unwrap();
+        let answer = runner.part(1, runner.part_input(&parsed), &params).unwrap();
+        assert_eq!(answer.to_string(), "4869534918");
+        assert!(change("rounds", "x").is_err());
+        assert!(change("laps", "1").is_err());
+        assert!(select(1, &[])[0]
+            .params(&[("key".to_string(), "1".to_string())])
+            .is_err());
+    }
+}
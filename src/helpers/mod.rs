@@ -1,5 +1,7 @@
 #![allow(dead_code)]
 pub mod grid2d;
+pub mod ocr;
+pub mod parse;
 
 use std::ops::{Range, RangeBounds};
 pub trait BorrowTwo<T> {
@@ -53,7 +55,8 @@ impl<const SIZE: usize> LongBitArr<SIZE> {
 pub struct BitIterator<T>(T);
 macro_rules! impl_BitIterator {
     ($($t:ty),+) => {
-        $(impl Iterator for BitIterator<$t> {
+        $(#[allow(clippy::cast_possible_truncation)]
+        impl Iterator for BitIterator<$t> {
             type Item = $t;
             fn next(&mut self) -> Option<Self::Item> {
                 if self.0 == 0 {
@@ -135,7 +138,7 @@ impl<T: Ord + Copy> MergedRange<T> {
         }
     }
     fn simplify(&mut self) {
-        self.ranges.sort_unstable_by(|a, b| a.start.cmp(&b.start));
+        self.ranges.sort_unstable_by_key(|a| a.start);
         'outer: loop {
             for index in 0..self.ranges.len() - 1 {
                 let (a, b) = self.ranges.borrow_two(index, index + 1);
@@ -0,0 +1,92 @@
+const HEIGHT: usize = 6;
+
+/// Every known letter, with `#` for lit pixels. All are 4 pixels wide,
+/// except for the 5 wide `Y`.
+const FONT: &[(char, [&str; HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// The capital letters drawn in `rows` in the 6 pixel high font that some
+/// puzzles use, with a dark column between the letters. `#` is a lit pixel
+/// and anything else a dark one. `None` unless every letter is known.
+pub fn read_letters<S: AsRef<str>>(rows: &[S]) -> Option<String> {
+    if rows.len() != HEIGHT {
+        return None;
+    }
+    let pixels: Vec<Vec<bool>> = rows
+        .iter()
+        .map(|row| row.as_ref().trim_end().chars().map(|c| c == '#').collect())
+        .collect();
+    let lit = |x: usize, y: usize| pixels[y].get(x).copied().unwrap_or(false);
+    let width = pixels.iter().map(Vec::len).max()?;
+    if width == 0 {
+        return None;
+    }
+    let mut letters = String::new();
+    let mut left = 0;
+    while left < width {
+        let (letter, glyph) = FONT.iter().find(|(_, glyph)| {
+            let glyph_width = glyph[0].len();
+            (0..HEIGHT).all(|y| !lit(left + glyph_width, y))
+                && glyph.iter().enumerate().all(|(y, row)| {
+                    row.chars()
+                        .enumerate()
+                        .all(|(x, c)| (c == '#') == lit(left + x, y))
+                })
+        })?;
+        letters.push(*letter);
+        left += glyph[0].len() + 1;
+    }
+    Some(letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn letters() {
+        let rows = [
+            "#..#..###.####.",
+            "#..#...#..#....",
+            "####...#..###..",
+            "#..#...#..#....",
+            "#..#...#..#....",
+            "#..#..###.#....",
+        ];
+        assert_eq!(read_letters(&rows).as_deref(), Some("HIF"));
+        assert_eq!(read_letters(&rows[..5]), None);
+        let mut unknown = rows.map(str::to_string);
+        unknown[0].replace_range(0..1, ".");
+        assert_eq!(read_letters(&unknown), None);
+        assert_eq!(read_letters(&["", "", "", "", "", ""]), None);
+    }
+    #[test]
+    fn wide_letter() {
+        let rows = [
+            "#..#.#...#.####",
+            "#..#.#...#....#",
+            "####..#.#....#.",
+            "#..#...#....#..",
+            "#..#...#...#...",
+            "#..#...#...####",
+        ];
+        assert_eq!(read_letters(&rows).as_deref(), Some("HYZ"));
+    }
+}
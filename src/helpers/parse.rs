@@ -0,0 +1,317 @@
+use itertools::Itertools;
+use std::{error::Error, fmt, ops::RangeInclusive, str::FromStr};
+
+/// Where and why an input couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column in characters, starting at 1.
+    pub column: usize,
+    /// The offending text, empty when the line or the input ended too early.
+    pub found: String,
+    /// What was expected instead, like `a number` or `"noop" or "addx"`.
+    pub expected: String,
+    /// The whole offending line.
+    pub source: String,
+}
+impl ParseError {
+    /// The offending line with carets under the offending text:
+    /// ```text
+    ///   |
+    /// 3 | addx x1
+    ///   |      ^^ expected a number
+    /// ```
+    pub fn diagram(&self) -> String {
+        let number = self.line.to_string();
+        let margin = " ".repeat(number.len());
+        format!(
+            "{margin} |\n{number} | {}\n{margin} | {}{} expected {}",
+            self.source,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.found.chars().count().max(1)),
+            self.expected
+        )
+    }
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Expected {} at line {}, column {}, found ",
+            self.expected, self.line, self.column
+        )?;
+        if self.found.is_empty() {
+            write!(f, "the end of the line")
+        } else {
+            write!(f, "{:?}", self.found)
+        }
+    }
+}
+impl Error for ParseError {}
+
+/// Numbered lines of an input, see [`lines`].
+#[derive(Debug, Clone)]
+pub struct Lines<'a> {
+    lines: std::str::Lines<'a>,
+    number: usize,
+}
+impl<'a> Lines<'a> {
+    /// An error for the line after the last one that was taken.
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.number + 1,
+            column: 1,
+            found: String::new(),
+            expected: expected.into(),
+            source: String::new(),
+        }
+    }
+    /// The next line, or an error when the input ended.
+    pub fn expect_line(&mut self, expected: &str) -> Result<Line<'a>, ParseError> {
+        self.next().ok_or_else(|| self.error(expected))
+    }
+    /// The lines up to the next empty line, which is skipped.
+    pub fn paragraph(&mut self) -> impl Iterator<Item = Line<'a>> + '_ {
+        self.take_while(|line| !line.text().is_empty())
+    }
+}
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+    fn next(&mut self) -> Option<Line<'a>> {
+        let text = self.lines.next()?;
+        self.number += 1;
+        Some(Line::new(self.number, text))
+    }
+}
+
+pub fn lines(input: &str) -> Lines<'_> {
+    Lines {
+        lines: input.lines(),
+        number: 0,
+    }
+}
+
+/// A line of input with a cursor, for parsing it from left to right.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub number: usize,
+    text: &'a str,
+    pos: usize,
+}
+impl<'a> Line<'a> {
+    pub const fn new(number: usize, text: &'a str) -> Self {
+        Self {
+            number,
+            text,
+            pos: 0,
+        }
+    }
+    /// The whole line, regardless of the cursor.
+    pub const fn text(&self) -> &'a str {
+        self.text
+    }
+    /// The part of the line after the cursor.
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+    pub const fn is_empty(&self) -> bool {
+        self.pos == self.text.len()
+    }
+    /// Byte offset of the cursor.
+    pub const fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// An error for the `len` bytes starting at byte `pos` of the line.
+    pub fn error_at(&self, pos: usize, len: usize, expected: impl Into<String>) -> ParseError {
+        let end = (pos + len).min(self.text.len());
+        ParseError {
+            line: self.number,
+            column: self.text[..pos].chars().count() + 1,
+            found: self.text[pos..end].to_string(),
+            expected: expected.into(),
+            source: self.text.to_string(),
+        }
+    }
+    /// An error for the word at the cursor.
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        let rest = self.rest();
+        let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        // Point at a single character, not the whole rest of the line.
+        let len = if len == 0 {
+            rest.chars().next().map_or(0, char::len_utf8)
+        } else {
+            len
+        };
+        self.error_at(self.pos, len, expected)
+    }
+
+    /// Makes sure every character of the line is valid.
+    pub fn check_chars(
+        &self,
+        expected: &str,
+        valid: impl Fn(char) -> bool,
+    ) -> Result<(), ParseError> {
+        match self.text.char_indices().find(|&(_, c)| !valid(c)) {
+            Some((pos, c)) => Err(self.error_at(pos, c.len_utf8(), expected)),
+            None => Ok(()),
+        }
+    }
+
+    /// Skips `literal`.
+    pub fn literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        let rest = self.rest();
+        if rest.starts_with(literal) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            let len = rest
+                .char_indices()
+                .nth(literal.chars().count())
+                .map_or(rest.len(), |(i, _)| i);
+            Err(self.error_at(self.pos, len, format!("{literal:?}")))
+        }
+    }
+    /// Parses a whole number with an optional sign.
+    pub fn number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with(['-', '+']));
+        let len = sign
+            + rest[sign..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len() - sign);
+        if len == sign {
+            return Err(self.error("a number"));
+        }
+        let number = rest[..len]
+            .parse()
+            .map_err(|_| self.error_at(self.pos, len, "a smaller number"))?;
+        self.pos += len;
+        Ok(number)
+    }
+    /// Parses a whole number that has to be in `range`.
+    pub fn number_in<T>(&mut self, range: RangeInclusive<T>) -> Result<T, ParseError>
+    where
+        T: FromStr + PartialOrd + fmt::Display,
+    {
+        let start = self.pos;
+        let number = self.number()?;
+        if range.contains(&number) {
+            Ok(number)
+        } else {
+            Err(self.error_at(
+                start,
+                self.pos - start,
+                format!("a number from {} to {}", range.start(), range.end()),
+            ))
+        }
+    }
+    /// Takes the letters and digits at the cursor.
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !c.is_alphanumeric())
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("a word"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+    /// Takes the next character.
+    pub fn char(&mut self) -> Result<char, ParseError> {
+        let c = self
+            .rest()
+            .chars()
+            .next()
+            .ok_or_else(|| self.error("a character"))?;
+        self.pos += c.len_utf8();
+        Ok(c)
+    }
+    /// Takes the text up to `delimiter`, and skips the delimiter.
+    pub fn until(&mut self, delimiter: &str) -> Result<&'a str, ParseError> {
+        let rest = self.rest();
+        let len = rest
+            .find(delimiter)
+            .ok_or_else(|| self.error_at(self.text.len(), 0, format!("{delimiter:?}")))?;
+        self.pos += len + delimiter.len();
+        Ok(&rest[..len])
+    }
+    /// Skips the first matching keyword and returns its value.
+    pub fn one_of<T: Copy>(&mut self, options: &[(&str, T)]) -> Result<T, ParseError> {
+        for &(keyword, value) in options {
+            if self.rest().starts_with(keyword) {
+                self.pos += keyword.len();
+                return Ok(value);
+            }
+        }
+        Err(self.error(
+            options
+                .iter()
+                .map(|(keyword, _)| format!("{keyword:?}"))
+                .join(" or "),
+        ))
+    }
+    /// Parses items separated by `separator`, up to the end of the line.
+    pub fn separated<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut items = vec![item(self)?];
+        while !self.is_empty() {
+            self.literal(separator)?;
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+    /// Makes sure the whole line was parsed.
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error_at(self.pos, self.text.len() - self.pos, "the end of the line"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn line() {
+        let mut line = Line::new(3, "move 12 from -3 to x");
+        line.literal("move ").unwrap();
+        assert_eq!(line.number::<u8>(), Ok(12));
+        assert_eq!(line.one_of(&[(" from ", 1), (" to ", 2)]), Ok(1));
+        assert_eq!(line.number::<i32>(), Ok(-3));
+        line.literal(" to ").unwrap();
+        let error = line.number::<u8>().unwrap_err();
+        assert_eq!((error.line, error.column), (3, 20));
+        assert_eq!(error.found, "x");
+        assert_eq!(
+            error.diagram(),
+            "  |\n3 | move 12 from -3 to x\n  |                    ^ expected a number"
+        );
+        assert_eq!(line.word(), Ok("x"));
+        assert!(line.end().is_ok());
+    }
+    #[test]
+    fn errors() {
+        let mut line = Line::new(1, "12, 300");
+        assert_eq!(line.separated(", ", Line::number::<u16>), Ok(vec![12, 300]));
+        let mut line = Line::new(1, "12, 300");
+        let error = line.separated(", ", Line::number::<u8>).unwrap_err();
+        assert_eq!((error.column, &error.found[..]), (5, "300"));
+        let error = Line::new(1, "noop").literal("addx").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected \"addx\" at line 1, column 1, found \"noop\""
+        );
+        let mut input = lines("a\n\nb");
+        assert_eq!(input.paragraph().count(), 1);
+        assert_eq!(input.expect_line("b").unwrap().text(), "b");
+        assert_eq!(input.expect_line("c").unwrap_err().line, 4);
+    }
+}
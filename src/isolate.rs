@@ -0,0 +1,309 @@
+use crate::{
+    answer::Answer,
+    days::registry::{self, Params, Parsed, Runner},
+    memory::{self, Allocations},
+};
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+use std::{
+    cell::{Cell, RefCell},
+    env, error, fmt,
+    io::{self, Read, Write},
+    panic::{self, AssertUnwindSafe},
+    process::{self, ExitCode, Stdio},
+    sync::Once,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// First argument of the child processes started by [`solve_in_child`].
+pub const CHILD_FLAG: &str = "--solve-in-child";
+
+thread_local! {
+    /// Whether a [`catch`] is running on this thread.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    /// Where the last panic on this thread happened.
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Reports caught panics only through [`catch`], and every other one as usual.
+fn install_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            LOCATION.set(info.location().map(ToString::to_string));
+            if !(cfg!(panic = "unwind") && CATCHING.get()) {
+                default(info);
+            }
+        }));
+    });
+}
+
+/// Runs `f`, turning a panic into an error with the panic message and where
+/// it happened. Only builds that unwind can catch panics, like the dev and
+/// `runner` profiles, the release profile aborts on the first one.
+///
+/// Panics on other threads, like those of rayon, are caught once they reach
+/// this one, but without their location.
+pub fn catch<R>(f: impl FnOnce() -> Result<R>) -> Result<R> {
+    install_hook();
+    LOCATION.take();
+    let catching = CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(catching);
+    result.unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        Err(match LOCATION.take() {
+            Some(location) => anyhow!("Panicked at {location}: {message}"),
+            None => anyhow!("Panicked: {message}"),
+        })
+    })
+}
+
+/// What running a part, or both parts together, gave.
+pub struct Solved {
+    /// The answer of the part, or the answers of both parts.
+    pub answers: Result<Vec<Answer>>,
+    pub time: Duration,
+    /// Time spent copying the parsed input, only for days whose parts consume it.
+    pub clone_time: Option<Duration>,
+    pub allocations: Option<Allocations>,
+    /// Time the child process spent parsing the input, only from
+    /// [`solve_in_child`].
+    pub parse_time: Option<Duration>,
+}
+
+/// Runs part `part` 0 or 1, or both parts together without a `part`, and
+/// catches its panics.
+pub fn solve(runner: &Runner, parsed: &Parsed, params: &Params, part: Option<usize>) -> Solved {
+    let start_time = Instant::now();
+    let part_input = runner.part_input(parsed);
+    let clone_time = runner.consumes().then(|| start_time.elapsed());
+    let start_time = Instant::now();
+    let (answers, allocations) = memory::measure(|| {
+        catch(|| match part {
+            Some(part) => Ok(vec![runner.part(part, part_input, params)?]),
+            None => Ok(runner.both(part_input, params)?.into()),
+        })
+    });
+    Solved {
+        answers,
+        time: start_time.elapsed(),
+        clone_time,
+        allocations,
+        parse_time: None,
+    }
+}
+
+/// Error of a part that didn't finish in time, see [`solve_in_child`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut(pub Duration);
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Timed out after {:?}", self.0)
+    }
+}
+impl error::Error for TimedOut {}
+
+/// Like [`solve`], but parses `input` and solves it in a child process that
+/// is killed after `timeout`, which includes starting it and parsing. The
+/// time is still only that of solving, the parse time is reported apart.
+/// Crashes of the child, like aborting panics, make the part fail.
+pub fn solve_in_child(
+    runner: &Runner,
+    input: &str,
+    changes: &[(String, String)],
+    part: Option<usize>,
+    timeout: Duration,
+) -> Solved {
+    let failed = |answers| Solved {
+        answers,
+        time: Duration::ZERO,
+        clone_time: None,
+        allocations: None,
+        parse_time: None,
+    };
+    let child = env::current_exe().and_then(|exe| {
+        process::Command::new(exe)
+            .arg(CHILD_FLAG)
+            .arg(runner.name)
+            .arg(part.map_or_else(|| "both".to_string(), |part| (part + 1).to_string()))
+            .args(
+                changes
+                    .iter()
+                    .map(|(name, value)| format!("{name}={value}")),
+            )
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+    });
+    let mut child = match child.context("Can't start a child process") {
+        Ok(child) => child,
+        Err(e) => return failed(Err(e)),
+    };
+    let start_time = Instant::now();
+    let (mut stdin, stdout, stderr) = (
+        child.stdin.take().unwrap(),
+        child.stdout.take().unwrap(),
+        child.stderr.take().unwrap(),
+    );
+    thread::scope(|scope| {
+        // Errors writing the input show as the child not finding it.
+        scope.spawn(move || stdin.write_all(input.as_bytes()));
+        let read_all = |mut pipe: Box<dyn Read + Send>| {
+            scope.spawn(move || {
+                let mut text = String::new();
+                pipe.read_to_string(&mut text).map(|_| text)
+            })
+        };
+        let (stdout, stderr) = (read_all(Box::new(stdout)), read_all(Box::new(stderr)));
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if start_time.elapsed() < timeout => {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                result => {
+                    // Killing fails when the child just exited, waiting then
+                    // still gets rid of it.
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    let e = match result {
+                        Err(e) => anyhow!(e).context("Can't wait for the child process"),
+                        _ => TimedOut(timeout).into(),
+                    };
+                    return Solved {
+                        answers: Err(e),
+                        time: timeout,
+                        ..failed(Ok(vec![]))
+                    };
+                }
+            }
+        };
+        let stdout = stdout.join().unwrap().unwrap_or_default();
+        let stderr = stderr.join().unwrap().unwrap_or_default();
+        read_solved(&stdout, part).unwrap_or_else(|| {
+            let context = format!("Child process failed ({status})");
+            let stderr = stderr.trim();
+            failed(Err(if stderr.is_empty() {
+                anyhow!(context)
+            } else {
+                anyhow!(stderr.to_string()).context(context)
+            }))
+        })
+    })
+}
+
+/// What a child process printed as its last line, see [`child_main`].
+fn read_solved(stdout: &str, part: Option<usize>) -> Option<Solved> {
+    let solved: Value = serde_json::from_str(stdout.lines().last()?).ok()?;
+    let answers = match &solved["answers"] {
+        Value::Array(answers) => answers
+            .iter()
+            .map(Answer::from_json)
+            .collect::<Option<Vec<_>>>(),
+        _ => None,
+    };
+    let answers = match answers {
+        Some(answers) if answers.len() == part.map_or(2, |_| 1) => Ok(answers),
+        Some(_) => return None,
+        None => Err(anyhow!("{}", solved["error"].as_str()?)),
+    };
+    let nanos = |key| solved[key].as_u64().map(Duration::from_nanos);
+    let allocations = &solved["allocations"];
+    Some(Solved {
+        answers,
+        time: nanos("time_ns").unwrap_or_default(),
+        clone_time: nanos("clone_ns"),
+        allocations: allocations.is_object().then(|| Allocations {
+            bytes: allocations["bytes"].as_u64().unwrap_or_default(),
+            count: allocations["count"].as_u64().unwrap_or_default(),
+            peak: allocations["peak_bytes"].as_u64().unwrap_or_default(),
+        }),
+        parse_time: nanos("parse_ns"),
+    })
+}
+
+/// Solves a part for [`solve_in_child`], with the arguments after
+/// [`CHILD_FLAG`]: `<runner> <1|2|both> [NAME=VALUE]...` and the input on
+/// stdin. Prints what was solved as a line of JSON.
+pub fn child_main(args: &[String]) -> ExitCode {
+    let solved = (|| {
+        let [name, part, changes @ ..] = args else {
+            bail!("Expected a runner and a part");
+        };
+        let runner = registry::RUNNERS
+            .iter()
+            .find(|runner| runner.name == name)
+            .with_context(|| format!("Unknown runner {name}"))?;
+        let part = match &part[..] {
+            "1" => Some(0),
+            "2" => Some(1),
+            "both" => None,
+            _ => bail!("Invalid part {part}"),
+        };
+        let changes: Vec<_> = changes
+            .iter()
+            .filter_map(|change| change.split_once('='))
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let params = runner.params(&changes)?;
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        let start_time = Instant::now();
+        let parsed = catch(|| runner.parse(input))?;
+        let parse_time = start_time.elapsed();
+        Ok(Solved {
+            parse_time: Some(parse_time),
+            ..solve(runner, &parsed, &params, part)
+        })
+    })();
+    let nanos = |time: Duration| u64::try_from(time.as_nanos()).unwrap_or(u64::MAX);
+    let solved = match solved {
+        Ok(Solved {
+            answers: Ok(answers),
+            time,
+            clone_time,
+            allocations,
+            parse_time,
+        }) => json!({
+            "answers": answers.iter().map(Answer::to_json).collect::<Vec<_>>(),
+            "time_ns": nanos(time),
+            "clone_ns": clone_time.map(nanos),
+            "parse_ns": parse_time.map(nanos),
+            "allocations": memory::to_json(allocations),
+        }),
+        Ok(Solved {
+            answers: Err(e), ..
+        })
+        | Err(e) => json!({ "error": format!("{e:#}") }),
+    };
+    println!("{solved}");
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn catches_panics() {
+        assert_eq!(catch(|| Ok(1)).unwrap(), 1);
+        assert!(catch(|| Err::<(), _>(anyhow!("error"))).is_err());
+        let error = catch(|| -> Result<()> { panic!("{} rooms", 65) }).unwrap_err();
+        let error = error.to_string();
+        assert!(error.starts_with("Panicked at src/isolate.rs:"), "{error}");
+        assert!(error.ends_with(": 65 rooms"), "{error}");
+        let error = catch(|| -> Result<()> {
+            rayon::join(|| (), || panic!("in a worker"));
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(error.to_string().ends_with("in a worker"));
+    }
+}
@@ -6,161 +6,1195 @@
     clippy::manual_range_contains,
     clippy::similar_names,
     clippy::enum_glob_use,
-    clippy::cast_sign_loss
+    clippy::cast_sign_loss,
+    clippy::ignored_unit_patterns
 )]
 
+use answer::Answer;
+use cli::{Command, Format, Options};
 use itertools::Itertools;
 use std::{
+    cell::Cell,
     collections::HashMap,
+    fmt::Write,
     fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::ExitCode,
     time::{Duration, Instant},
 };
+mod answer;
+mod baseline;
+mod bench;
+use bench::Stats;
+mod cli;
 mod days;
-use days::*;
+use days::{
+    day::Example,
+    registry::{self, Runner},
+};
 mod helpers;
+use helpers::parse::ParseError;
+mod isolate;
+mod memory;
+use memory::Allocations;
+mod report;
+use report::{InputRun, PartRun, Status, Summary};
 mod results;
+use results::DayResults;
+mod schedule;
 
 const TEXT_RED: &str = "\x1b[1;31m";
 const TEXT_YELLOW: &str = "\x1b[1;33m";
 const TEXT_RESET: &str = "\x1b[0m";
 const TEXT_HEADER: &str = "\x1b[2;30;47m";
 
-fn header(header: &str) {
-    println!("\n{TEXT_HEADER}{header:#^60}{TEXT_RESET}");
-}
-
-fn run<T: day::Day>(
-    file: &str,
-    results: &[Option<String>; 2],
-) -> Option<(
-    std::time::Duration,
-    std::time::Duration,
-    std::time::Duration,
-)> {
-    let Ok(input) = fs::read_to_string(file) else {
-        println!("{TEXT_YELLOW}No input file found:{TEXT_RESET}");
-        return None;
-    };
+fn header(out: &mut String, header: &str) {
+    writeln!(out, "\n{TEXT_HEADER}{header:#^60}{TEXT_RESET}").unwrap();
+}
+
+fn read_input(file: &Path) -> io::Result<String> {
+    if file == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(file)
+    }
+}
+
+fn print_stats(out: &mut String, phase: &str, stats: &Stats) {
+    writeln!(
+        out,
+        "  {phase:<8}|{:^13}|{:^13}|{:^13}|{:^13}|{:^13}|{:^8}",
+        format!("{:?}", stats.min),
+        format!("{:?}", stats.median),
+        format!("{:?}", stats.mean),
+        format!("{:?}", stats.p95),
+        format!("{:?}", stats.stddev),
+        stats.batch
+    )
+    .unwrap();
+}
+
+fn print_benchmark(
+    out: &mut String,
+    samples: usize,
+    parse: &Stats,
+    parts: &[Option<PartRun>],
+    both: Option<&Stats>,
+) {
+    writeln!(out, "- Benchmark, {samples} samples:").unwrap();
+    writeln!(
+        out,
+        "  Phase   |{:^13}|{:^13}|{:^13}|{:^13}|{:^13}|{:^8}",
+        "min", "median", "mean", "p95", "stddev", "batch"
+    )
+    .unwrap();
+    print_stats(out, "Parsing", parse);
+    for (i, part) in parts.iter().enumerate() {
+        if let Some(PartRun {
+            stats: Some(stats), ..
+        }) = part
+        {
+            print_stats(out, &format!("Part {}", i + 1), stats);
+        }
+    }
+    if let Some(stats) = both {
+        print_stats(out, "Both", stats);
+    }
+}
+
+/// Prints the answer of a part, or why it failed, and checks the answer.
+fn part_outcome(
+    out: &mut String,
+    part: usize,
+    result: anyhow::Result<Answer>,
+    stored: &results::InputResults,
+) -> (Answer, Status) {
+    writeln!(out, "- Part {}:", part + 1).unwrap();
+    match result {
+        Ok(answer) => {
+            writeln!(out, "-- Result:\n{answer}").unwrap();
+            let status = check_answer(out, &answer, stored.expected[part].as_ref());
+            if status == Status::New {
+                if let Some(warning) = stored.hints[part].check(&answer) {
+                    writeln!(out, "{TEXT_YELLOW}{warning}{TEXT_RESET}").unwrap();
+                }
+            }
+            (answer, status)
+        }
+        Err(e) => {
+            print_failure(out, &format!("Part {}", part + 1), &e);
+            (Answer::Text(format!("{e:#}")), failure(&e))
+        }
+    }
+}
+
+/// Prints that `what` failed with `e`, or that it timed out.
+fn print_failure(out: &mut String, what: &str, e: &anyhow::Error) {
+    if let Some(isolate::TimedOut(timeout)) = e.downcast_ref() {
+        writeln!(
+            out,
+            "{TEXT_RED}{what} timed out{TEXT_RESET} after {timeout:?}"
+        )
+        .unwrap();
+    } else {
+        writeln!(out, "{TEXT_RED}{what} failed:{TEXT_RESET} {e:?}").unwrap();
+    }
+}
+
+/// The status of a part that ran into `e`.
+fn failure(e: &anyhow::Error) -> Status {
+    if e.is::<isolate::TimedOut>() {
+        Status::TimedOut
+    } else {
+        Status::Failed
+    }
+}
 
-    let start_time = Instant::now();
-    let parsed = T::parse(input);
-    let parsed_time = start_time.elapsed();
-    let parsed = parsed.expect(&format!("Error parsing file {file}")[..]);
-
-    let mut times = (0..=1).map(|i| {
-        println!("- Part {}:", i + 1);
-        let cloned = parsed.clone();
-        let start_time = Instant::now();
-        let result = if i == 0 {
-            T::first(cloned)
+fn check_answer(out: &mut String, answer: &Answer, expected: Option<&Answer>) -> Status {
+    if let Some(saved_result) = expected {
+        if answer.matches(saved_result) {
+            Status::Match
         } else {
-            T::second(cloned)
-        };
-        let elapsed = start_time.elapsed();
-        println!("-- Result:\n{}", result.to_string());
-        match &results[i] {
-            Some(saved_result) => {
-                if result.to_string() != *saved_result {
-                    println!("{TEXT_RED}Results do not match! Stored result:{TEXT_RESET}\n{saved_result}");
+            writeln!(
+                out,
+                "{TEXT_RED}Results do not match! Stored result:{TEXT_RESET}\n{saved_result}"
+            )
+            .unwrap();
+            Status::Mismatch
+        }
+    } else {
+        writeln!(out, "{TEXT_YELLOW}New result found!{TEXT_RESET}").unwrap();
+        Status::New
+    }
+}
+
+/// Prints why an input couldn't be parsed, pointing at the offending text
+/// when the parser knows where it is.
+fn print_parse_error(out: &mut String, file: &Path, e: &anyhow::Error) {
+    writeln!(
+        out,
+        "{TEXT_RED}Error parsing file {}:{TEXT_RESET} {e:?}",
+        file.display()
+    )
+    .unwrap();
+    if let Some(error) = e.chain().find_map(|e| e.downcast_ref::<ParseError>()) {
+        writeln!(out, "{}", error.diagram()).unwrap();
+    }
+}
+
+/// An input file of a day and its contents.
+struct Input {
+    name: String,
+    /// The file it was read from, or the name of the example.
+    file: PathBuf,
+    text: String,
+}
+
+/// How the parts of a [`Job`] are solved.
+enum Solver {
+    /// In this process, with the input parsed already.
+    InProcess(registry::Parsed),
+    /// In child processes that parse the input themselves and are killed
+    /// after the timeout.
+    InChild(Duration),
+}
+
+/// An input of a runner, with the parameters to run its parts with.
+struct Job<'a> {
+    runner: &'a Runner,
+    input: &'a Input,
+    /// The parameter changes `params` were made with.
+    changes: &'a [(String, String)],
+    solver: Solver,
+    params: registry::Params,
+    /// How long the first child process took to parse the input.
+    child_parse_time: Cell<Option<Duration>>,
+}
+impl Job<'_> {
+    /// Runs part `part` 0 or 1, or both parts together without a `part`.
+    fn solve(&self, part: Option<usize>) -> isolate::Solved {
+        match &self.solver {
+            Solver::InProcess(parsed) => isolate::solve(self.runner, parsed, &self.params, part),
+            Solver::InChild(timeout) => {
+                let solved = isolate::solve_in_child(
+                    self.runner,
+                    &self.input.text,
+                    self.changes,
+                    part,
+                    *timeout,
+                );
+                if self.child_parse_time.get().is_none() {
+                    self.child_parse_time.set(solved.parse_time);
                 }
+                solved
             }
-            None => {
-                println!("{TEXT_YELLOW}New result found!{TEXT_RESET}");
+        }
+    }
+
+    /// The parsed input, unless only the child processes have it.
+    const fn parsed(&self) -> Option<&registry::Parsed> {
+        match &self.solver {
+            Solver::InProcess(parsed) => Some(parsed),
+            Solver::InChild(_) => None,
+        }
+    }
+}
+
+/// Where an input of a day comes from.
+enum Source {
+    File(PathBuf),
+    Example(&'static Example),
+}
+
+/// A run whose parts all failed with `e`, without running them.
+fn failed_run(
+    runner: &Runner,
+    input: &Input,
+    options: &Options,
+    stored: &results::InputResults,
+    summary: &mut Summary,
+    parse_time: Duration,
+    e: &anyhow::Error,
+) -> InputRun {
+    let mut failed = (0..=1).map(|i| {
+        options.parts[i].then(|| {
+            summary.count(Status::Failed);
+            PartRun {
+                answer: Answer::Text(format!("{e:#}")),
+                expected: stored.expected[i].clone(),
+                time: Duration::ZERO,
+                clone_time: None,
+                stats: None,
+                allocations: None,
+                status: Status::Failed,
+            }
+        })
+    });
+    InputRun {
+        day: runner.day,
+        input: input.name.clone(),
+        variant: runner.variant,
+        parse_time,
+        parse_stats: None,
+        parse_allocations: None,
+        parts: [failed.next().unwrap(), failed.next().unwrap()],
+        both_time: None,
+        both_stats: None,
+        both_allocations: None,
+    }
+}
+
+/// Whether a `--param` changes a parameter of `runner`.
+fn overrides(param: &cli::ParamOverride, runner: &Runner) -> bool {
+    param.day.is_none_or(|day| day == runner.day)
+        && runner
+            .default_params()
+            .iter()
+            .any(|(name, _)| *name == param.name)
+}
+
+fn run(
+    out: &mut String,
+    runner: &Runner,
+    input: &Input,
+    options: &Options,
+    stored: &results::InputResults,
+    summary: &mut Summary,
+) -> InputRun {
+    let overridden: Vec<_> = options
+        .params
+        .iter()
+        .filter(|param| overrides(param, runner))
+        .map(|param| (param.name.clone(), param.value.clone()))
+        .collect();
+    // The stored answers and hints are for the stored parameters only.
+    let stored = &if overridden.is_empty() {
+        stored.clone()
+    } else {
+        results::InputResults {
+            params: stored.params.clone(),
+            ..Default::default()
+        }
+    };
+    let changes: Vec<_> = stored.params.iter().cloned().chain(overridden).collect();
+    if !changes.is_empty() {
+        let changes = changes
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"));
+        writeln!(out, "- Parameters: {}", changes.format(", ")).unwrap();
+    }
+    let params = match runner.params(&changes) {
+        Ok(params) => params,
+        Err(e) => {
+            writeln!(out, "{TEXT_RED}Invalid parameters:{TEXT_RESET} {e:?}").unwrap();
+            return failed_run(runner, input, options, stored, summary, Duration::ZERO, &e);
+        }
+    };
+
+    // With a timeout, parsing is left to the child processes, so that it is
+    // limited too.
+    let (solver, mut parse_time, parse_allocations) = if let Some(timeout) = options.timeout {
+        (Solver::InChild(timeout), Duration::ZERO, None)
+    } else {
+        let owned_input = input.text.clone();
+        let ((parsed, parse_time), parse_allocations) =
+            schedule::timed(options.serial_timing, || {
+                memory::measure(|| {
+                    let start_time = Instant::now();
+                    let parsed = isolate::catch(|| runner.parse(owned_input));
+                    (parsed, start_time.elapsed())
+                })
+            });
+        match parsed {
+            Ok(parsed) => (Solver::InProcess(parsed), parse_time, parse_allocations),
+            Err(e) => {
+                print_parse_error(out, &input.file, &e);
+                return failed_run(runner, input, options, stored, summary, parse_time, &e);
             }
         }
-        elapsed
+    };
+
+    let job = Job {
+        runner,
+        input,
+        changes: &changes,
+        solver,
+        params,
+        child_parse_time: Cell::new(None),
+    };
+    let (parts, both_time, both_stats, both_allocations) =
+        if runner.solves_both() && options.parts.iter().all(|&part| part) {
+            run_together(out, &job, options, stored, summary)
+        } else {
+            let parts = run_separately(out, &job, options, stored, summary);
+            (parts, None, None, None)
+        };
+    if let Some(child_parse_time) = job.child_parse_time.get() {
+        parse_time = child_parse_time;
+    }
+
+    let parse_stats = options.bench.map(|samples| {
+        let stats = schedule::timed(options.serial_timing, || {
+            bench::measure(samples, || input.text.clone(), |input| runner.parse(input))
+        });
+        parse_time = stats.median;
+        print_benchmark(out, samples, &stats, &parts, both_stats.as_ref());
+        stats
     });
+    if let Some(dir) = &options.dump {
+        dump(out, &job, dir);
+    }
+    InputRun {
+        day: runner.day,
+        input: input.name.clone(),
+        variant: runner.variant,
+        parse_time,
+        parse_stats,
+        parse_allocations,
+        parts,
+        both_time,
+        both_stats,
+        both_allocations,
+    }
+}
+
+/// Writes what solving the input looks like into `dir`, see [`Runner::dump`].
+fn dump(out: &mut String, job: &Job, dir: &Path) {
+    let Job {
+        runner,
+        input,
+        params,
+        ..
+    } = job;
+    let Some(parsed) = job.parsed() else {
+        return;
+    };
+    match isolate::catch(|| runner.dump(parsed, params, dir, &input.name)) {
+        Ok(Some(file)) => writeln!(out, "- Dumped into {}", file.display()).unwrap(),
+        Ok(None) => {}
+        Err(e) => writeln!(out, "{TEXT_RED}Can't dump:{TEXT_RESET} {e:?}").unwrap(),
+    }
+}
 
-    Some((parsed_time, times.next().unwrap(), times.next().unwrap()))
-}
-
-fn run_day(day: u8, results: &[Option<String>; 2]) -> Option<(Duration, Duration, Duration)> {
-    let file = format!("input/{day}.txt");
-    header(&format!(" Day {day} "));
-    match day {
-        1 => run::<day1::Day1>(&file, results),
-        2 => run::<day2::Day2>(&file, results),
-        3 => run::<day3::Day3>(&file, results),
-        4 => run::<day4::Day4>(&file, results),
-        5 => run::<day5::Day5>(&file, results),
-        6 => run::<day6::Day6>(&file, results),
-        7 => run::<day7::Day7>(&file, results),
-        8 => run::<day8::Day8>(&file, results),
-        9 => run::<day9::Day9>(&file, results),
-        10 => run::<day10::Day10>(&file, results),
-        11 => run::<day11::Day11>(&file, results),
-        12 => run::<day12::Day12>(&file, results),
-        13 => run::<day13::Day13>(&file, results),
-        14 => run::<day14::Day14>(&file, results),
-        15 => run::<day15::Day15>(&file, results),
-        16 => run::<day16::Day16>(&file, results),
-        17 => run::<day17::Day17>(&file, results),
-        18 => run::<day18::Day18>(&file, results),
-        19 => run::<day19::Day19>(&file, results),
-        20 => run::<day20::Day20>(&file, results),
-        21 => run::<day21::Day21>(&file, results),
-        22 => run::<day22::Day22>(&file, results),
-        23 => run::<day23::Day23>(&file, results),
-        24 => run::<day24::Day24>(&file, results),
-        25 => run::<day25::Day25>(&file, results),
-        _ => panic!(),
-    }
-}
-
-fn run_days(days: Vec<u8>) {
-    let mut timings: Vec<(u8, (Duration, Duration, Duration))> = Vec::new();
-
-    let results = results::load().unwrap_or_else(|e| {
-        println!("{TEXT_YELLOW}Can't load results.json file:{TEXT_RESET}\n  {e:?}");
-        HashMap::new()
+/// Runs the selected parts one after the other.
+fn run_separately(
+    out: &mut String,
+    job: &Job,
+    options: &Options,
+    stored: &results::InputResults,
+    summary: &mut Summary,
+) -> [Option<PartRun>; 2] {
+    let Job { runner, params, .. } = job;
+    let mut part_runs = (0..=1).map(|i| {
+        if !options.parts[i] {
+            return None;
+        }
+        let solved = schedule::timed(options.serial_timing, || job.solve(Some(i)));
+        let result = solved.answers.map(|mut answers| answers.swap_remove(0));
+        let (answer, status) = part_outcome(out, i, result, stored);
+        summary.count(status);
+        let (mut time, clone_time) = (solved.time, solved.clone_time);
+        let bench = options.bench.filter(|_| status.finished());
+        let stats = bench.zip(job.parsed()).map(|(samples, parsed)| {
+            schedule::timed(options.serial_timing, || {
+                bench::measure(
+                    samples,
+                    || runner.part_input(parsed),
+                    |input| runner.part(i, input, params),
+                )
+            })
+        });
+        if let Some(stats) = &stats {
+            time = stats.median;
+        }
+        Some(PartRun {
+            answer,
+            expected: stored.expected[i].clone(),
+            time,
+            clone_time,
+            stats,
+            allocations: solved.allocations,
+            status,
+        })
+    });
+    [part_runs.next().unwrap(), part_runs.next().unwrap()]
+}
+
+/// Runs both parts in one go, for days that share work between them. The
+/// parts get no times of their own, only the combined one.
+fn run_together(
+    out: &mut String,
+    job: &Job,
+    options: &Options,
+    stored: &results::InputResults,
+    summary: &mut Summary,
+) -> (
+    [Option<PartRun>; 2],
+    Option<Duration>,
+    Option<Stats>,
+    Option<Allocations>,
+) {
+    let Job { runner, params, .. } = job;
+    let solved = schedule::timed(options.serial_timing, || job.solve(None));
+    let (mut time, clone_time) = (solved.time, solved.clone_time);
+    let outcomes = match solved.answers {
+        Ok(answers) => {
+            let mut answers = answers.into_iter().enumerate();
+            [(); 2].map(|()| {
+                let (i, answer) = answers.next().unwrap();
+                part_outcome(out, i, Ok(answer), stored)
+            })
+        }
+        Err(e) => {
+            print_failure(out, "Parts 1 and 2", &e);
+            [(); 2].map(|()| (Answer::Text(format!("{e:#}")), failure(&e)))
+        }
+    };
+    let finished = outcomes.iter().all(|(_, status)| status.finished());
+    let bench = options.bench.filter(|_| finished);
+    let stats = bench.zip(job.parsed()).map(|(samples, parsed)| {
+        schedule::timed(options.serial_timing, || {
+            bench::measure(
+                samples,
+                || runner.part_input(parsed),
+                |input| runner.both(input, params),
+            )
+        })
     });
-    for day in days {
-        let result = results.get(&(day as usize)).unwrap_or(&[None, None]);
-        if let Some(t) = run_day(day, result) {
-            timings.push((day, t));
+    if let Some(stats) = &stats {
+        time = stats.median;
+    }
+    let mut i = 0;
+    let parts = outcomes.map(|(answer, status)| {
+        summary.count(status);
+        let part = PartRun {
+            answer,
+            expected: stored.expected[i].clone(),
+            time: Duration::ZERO,
+            // Both parts share a single copy.
+            clone_time: clone_time.filter(|_| i == 0),
+            stats: None,
+            allocations: None,
+            status,
+        };
+        i += 1;
+        Some(part)
+    });
+    (parts, Some(time), stats, solved.allocations)
+}
+
+/// Prints the timings of every variant that ran on an input next to each
+/// other, and checks that they all found the same answers as the first one.
+/// Returns the number of parts where a variant disagrees.
+fn compare_variants(out: &mut String, runs: &[InputRun]) -> usize {
+    let reference = &runs[0];
+    writeln!(out, "- Variants:").unwrap();
+    writeln!(
+        out,
+        "  {:<12}|{:^13}|{:^13}|{:^13}|{:^13}|",
+        "Variant", "Parsing", "Part 1", "Part 2", "Total"
+    )
+    .unwrap();
+    for run in runs {
+        writeln!(
+            out,
+            "  {:<12}|{:^13}|{:^13}|{:^13}|{:^13}| {:.2}x",
+            run.variant,
+            format!("{:?}", run.parse_time),
+            format_duration(run.part_time(0)),
+            format_duration(run.part_time(1)),
+            format!("{:?}", run.total_time()),
+            run.total_time().as_secs_f64() / reference.total_time().as_secs_f64()
+        )
+        .unwrap();
+    }
+    let mut disagreeing = 0;
+    for run in &runs[1..] {
+        for (i, (part, reference_part)) in run.parts.iter().zip(&reference.parts).enumerate() {
+            let (Some(part), Some(reference_part)) = (part, reference_part) else {
+                continue;
+            };
+            if !part.status.finished() || !reference_part.status.finished() {
+                continue;
+            }
+            if !part.answer.matches(&reference_part.answer) {
+                disagreeing += 1;
+                writeln!(
+                    out,
+                    "{TEXT_RED}Variant {} disagrees with {} on part {}:{TEXT_RESET}\n{}",
+                    run.variant,
+                    reference.variant,
+                    i + 1,
+                    part.answer
+                )
+                .unwrap();
+            }
+        }
+    }
+    disagreeing
+}
+
+/// Whether part `part` 0 or 1 doesn't run on `source`, because it's an
+/// example the puzzle text gives no answer of that part for.
+const fn skipped(source: &Source, part: usize) -> bool {
+    matches!(source, Source::Example(example) if example.answers[part].is_none())
+}
+
+/// The examples of a day as inputs, with their answers and parameters
+/// instead of stored results.
+fn example_inputs(example: &Example) -> (Input, results::InputResults) {
+    let input = Input {
+        name: example.name.to_string(),
+        file: PathBuf::from(example.name),
+        text: example.input.to_string(),
+    };
+    let stored = results::InputResults {
+        expected: example
+            .answers
+            .map(|answer| answer.map(|answer| Answer::Text(answer.to_string()))),
+        params: example
+            .params
+            .iter()
+            .map(|&(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+    (input, stored)
+}
+
+/// Every input for a day: `input/<day>.txt` as the default input, followed by
+/// `input/<day>/<name>.txt` in name order.
+fn find_inputs(day: u8, options: &Options) -> Vec<(String, PathBuf)> {
+    if let Some(file) = &options.input {
+        let name = match file.file_stem() {
+            Some(stem) if file != Path::new("-") => stem.to_string_lossy().into_owned(),
+            _ => "stdin".to_string(),
+        };
+        return vec![(name, file.clone())];
+    }
+    let default = options.input_dir.join(format!("{day}.txt"));
+    let mut named: Vec<_> = fs::read_dir(options.input_dir.join(day.to_string()))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? == "txt" {
+                Some((path.file_stem()?.to_string_lossy().into_owned(), path))
+            } else {
+                None
+            }
+        })
+        .collect();
+    named.sort();
+    if default.exists() || named.is_empty() {
+        named.insert(0, (results::DEFAULT_INPUT.to_string(), default));
+    }
+    named
+}
+
+fn run_day(
+    out: &mut String,
+    day: u8,
+    options: &Options,
+    results: &DayResults,
+    summary: &mut Summary,
+) -> Vec<InputRun> {
+    let runners = registry::select(day, &options.variants);
+    if runners.is_empty() {
+        return vec![];
+    }
+    header(out, &format!(" Day {day} "));
+    let inputs: Vec<_> = if options.example {
+        let examples = runners[0].examples;
+        if examples.is_empty() {
+            writeln!(out, "{TEXT_YELLOW}No examples for this day{TEXT_RESET}").unwrap();
+            summary.missing_input += options.parts.iter().filter(|&&part| part).count();
+        }
+        let examples = examples
+            .iter()
+            .map(|e| (e.name.to_string(), Source::Example(e)));
+        examples.collect()
+    } else {
+        let files = find_inputs(day, options).into_iter();
+        files
+            .map(|(name, file)| (name, Source::File(file)))
+            .collect()
+    };
+    let mut runs = vec![];
+    for (name, source) in &inputs {
+        if inputs.len() > 1 || name != results::DEFAULT_INPUT {
+            writeln!(out, "{:-^60}", format!(" Input: {name} ")).unwrap();
+        }
+        let (input, stored) = match source {
+            Source::Example(example) => example_inputs(example),
+            Source::File(file) => match read_input(file) {
+                Ok(text) => {
+                    let input = Input {
+                        name: name.clone(),
+                        file: file.clone(),
+                        text,
+                    };
+                    (input, results.get(name).cloned().unwrap_or_default())
+                }
+                Err(e) => {
+                    writeln!(
+                        out,
+                        "{TEXT_YELLOW}No input file found:{TEXT_RESET} {}\n  {e}",
+                        file.display()
+                    )
+                    .unwrap();
+                    summary.missing_input += options.parts.iter().filter(|&&part| part).count();
+                    continue;
+                }
+            },
+        };
+        for part in (0..=1).filter(|&part| options.parts[part] && skipped(source, part)) {
+            writeln!(
+                out,
+                "- Part {}: no answer in the puzzle text, skipped",
+                part + 1
+            )
+            .unwrap();
+        }
+        let input_runs: Vec<_> = runners
+            .iter()
+            .map(|runner| {
+                if runners.len() > 1 || runner.variant != registry::DEFAULT_VARIANT {
+                    writeln!(out, "{:.^60}", format!(" Variant: {} ", runner.variant)).unwrap();
+                }
+                let options = &Options {
+                    parts: [0, 1].map(|i| options.parts[i] && !skipped(source, i)),
+                    ..options.clone()
+                };
+                run(out, runner, &input, options, &stored, summary)
+            })
+            .collect();
+        if input_runs.len() > 1 {
+            summary.disagreeing += compare_variants(out, &input_runs);
         }
+        runs.extend(input_runs);
+    }
+    runs
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    duration.map_or_else(|| "-".to_string(), |d| format!("{d:?}"))
+}
+
+/// Human readable bytes, like `512 B` or `1.5 MiB`.
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    for unit in ["B", "KiB", "MiB", "GiB"] {
+        if size < 1024.0 || unit == "GiB" {
+            return if unit == "B" {
+                format!("{bytes} B")
+            } else {
+                format!("{size:.1} {unit}")
+            };
+        }
+        size /= 1024.0;
+    }
+    unreachable!()
+}
+
+/// Rows with the allocations of a phase, when they were counted.
+fn print_allocations(
+    out: &mut String,
+    chunk: &[&InputRun],
+    allocations: impl Fn(&InputRun) -> Option<Allocations>,
+) {
+    if chunk.iter().all(|run| allocations(run).is_none()) {
+        return;
     }
-    timings = timings
+    let cells = |a: Allocations| {
+        [
+            format_bytes(a.bytes),
+            a.count.to_string(),
+            format_bytes(a.peak),
+        ]
+    };
+    for (row, label) in ["  bytes: ", "  allocs:", "  peak:  "]
         .into_iter()
-        .sorted_by(|a, b| Ord::cmp(&(b.1 .0 + b.1 .1 + b.1 .2), &(a.1 .0 + a.1 .1 + a.1 .2)))
+        .enumerate()
+    {
+        write!(out, "|\n{label}").unwrap();
+        for run in chunk {
+            let cell = allocations(run).map_or_else(|| "-".to_string(), |a| cells(a)[row].clone());
+            write!(out, "|{cell:^15}").unwrap();
+        }
+    }
+}
+
+fn print_timings(out: &mut String, runs: &[InputRun], options: &Options) {
+    let runs: Vec<_> = runs
+        .iter()
+        .sorted_by(|a, b| Ord::cmp(&b.total_time(), &a.total_time()))
         .collect();
 
-    header(" TIMINGS ");
-    for chunk in timings.chunks(7) {
-        print!("Day:     ");
-        for (name, _) in chunk {
-            print!("|{name:^15}");
+    header(
+        out,
+        if options.bench.is_some() {
+            " TIMINGS (MEDIAN) "
+        } else {
+            " TIMINGS "
+        },
+    );
+    for chunk in runs.chunks(7) {
+        write!(out, "Day:     ").unwrap();
+        for run in chunk {
+            write!(out, "|{:^15}", run.label()).unwrap();
         }
-        print!("|\nParsing: ");
-        for (_, (a, _, _)) in chunk {
-            print!("|{:^15}", format!("{a:?}"));
+        write!(out, "|\nParsing: ").unwrap();
+        for run in chunk {
+            write!(out, "|{:^15}", format!("{:?}", run.parse_time)).unwrap();
+        }
+        print_allocations(out, chunk, |run| run.parse_allocations);
+        for part in 0..=1 {
+            let clone_time = |run: &InputRun| run.parts[part].as_ref()?.clone_time;
+            if chunk.iter().any(|run| clone_time(run).is_some()) {
+                write!(out, "|\nClone {}: ", part + 1).unwrap();
+                for run in chunk {
+                    write!(out, "|{:^15}", format_duration(clone_time(run))).unwrap();
+                }
+            }
+            write!(out, "|\nPart {}:  ", part + 1).unwrap();
+            for run in chunk {
+                write!(out, "|{:^15}", format_duration(run.part_time(part))).unwrap();
+            }
+            print_allocations(out, chunk, |run| run.parts[part].as_ref()?.allocations);
+        }
+        if chunk.iter().any(|run| run.both_time.is_some()) {
+            write!(out, "|\nBoth:    ").unwrap();
+            for run in chunk {
+                write!(out, "|{:^15}", format_duration(run.both_time)).unwrap();
+            }
+            print_allocations(out, chunk, |run| run.both_allocations);
         }
-        print!("|\nPart 1:  ");
-        for (_, (_, b, _)) in chunk {
-            print!("|{:^15}", format!("{b:?}"));
+        writeln!(out, "|").unwrap();
+        writeln!(out).unwrap();
+    }
+}
+
+fn print_results_matrix(out: &mut String, runs: &[InputRun]) {
+    let inputs: Vec<&String> = runs.iter().map(|run| &run.input).unique().collect();
+    let cell = |status: Option<Status>| match status {
+        Some(Status::Match) => ("ok", ""),
+        Some(Status::Mismatch) => ("FAIL", TEXT_RED),
+        Some(Status::New) => ("new", TEXT_YELLOW),
+        Some(Status::Failed) => ("ERR", TEXT_RED),
+        Some(Status::TimedOut) => ("TIME", TEXT_RED),
+        None => ("-", ""),
+    };
+
+    let rows: Vec<(u8, &str)> = runs
+        .iter()
+        .map(|run| (run.day, run.variant))
+        .unique()
+        .collect();
+    let row_label = |&(day, variant): &(u8, &str)| {
+        if variant == registry::DEFAULT_VARIANT {
+            day.to_string()
+        } else {
+            format!("{day}:{variant}")
+        }
+    };
+    let width = rows
+        .iter()
+        .map(|row| row_label(row).len() + 1)
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    header(out, " RESULTS ");
+    write!(out, "{:<width$}", "Day").unwrap();
+    for input in &inputs {
+        write!(out, "|{input:^13}").unwrap();
+    }
+    writeln!(out, "|").unwrap();
+    for row in &rows {
+        write!(out, "{:^width$}", row_label(row)).unwrap();
+        for &input in &inputs {
+            write!(out, "|").unwrap();
+            match runs
+                .iter()
+                .find(|run| (run.day, run.variant) == *row && &run.input == input)
+            {
+                Some(run) => {
+                    let [first, second] = &run.parts;
+                    let (first, first_color) = cell(first.as_ref().map(|p| p.status));
+                    let (second, second_color) = cell(second.as_ref().map(|p| p.status));
+                    let padding = 13 - first.len() - second.len() - 3;
+                    write!(out,
+                        "{:<left$}{first_color}{first}{TEXT_RESET} / {second_color}{second}{TEXT_RESET}{:<right$}",
+                        "",
+                        "",
+                        left = padding / 2,
+                        right = padding - padding / 2
+                    ).unwrap();
+                }
+                None => write!(out, "{:13}", "").unwrap(),
+            }
         }
-        print!("|\nPart 2:  ");
-        for (_, (_, _, c)) in chunk {
-            print!("|{:^15}", format!("{c:?}"));
+        writeln!(out, "|").unwrap();
+    }
+}
+
+fn print_summary(out: &mut String, summary: &Summary) {
+    let count = |count: usize, what: &str, color: &str| {
+        if count == 0 || color.is_empty() {
+            format!("{count} {what}")
+        } else {
+            format!("{color}{count} {what}{TEXT_RESET}")
         }
-        println!("|");
-        println!();
+    };
+    write!(
+        out,
+        "\n{}, {}, {}, {}, {}",
+        count(summary.passed, "passed", ""),
+        count(summary.mismatched, "mismatched", TEXT_RED),
+        count(summary.failed, "failed", TEXT_RED),
+        count(summary.new, "new", TEXT_YELLOW),
+        count(summary.missing_input, "missing input", TEXT_YELLOW)
+    )
+    .unwrap();
+    if summary.timed_out > 0 {
+        write!(out, ", {}", count(summary.timed_out, "timed out", TEXT_RED)).unwrap();
+    }
+    if summary.disagreeing > 0 {
+        write!(
+            out,
+            ", {}",
+            count(summary.disagreeing, "disagreeing", TEXT_RED)
+        )
+        .unwrap();
     }
+    writeln!(out).unwrap();
 }
 
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
+/// Prints and clears the human readable output. It goes to stdout, unless
+/// stdout is used for a machine readable report.
+fn emit(options: &Options, out: &mut String) {
+    if options.format == Format::Text {
+        print!("{out}");
+    } else {
+        eprint!("{out}");
+    }
+    out.clear();
+}
 
-    match args.len() {
-        1 => {
-            run_days((1..=25).into_iter().collect());
+fn compare_baseline(out: &mut String, runs: &[InputRun], options: &Options, file: &Path) -> bool {
+    let baseline = match baseline::load(file) {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            writeln!(
+                out,
+                "{TEXT_RED}Can't load baseline {}:{TEXT_RESET}\n  {e:?}",
+                file.display()
+            )
+            .unwrap();
+            return false;
         }
-        2 => match args[1].parse() {
-            Ok(day) if (1..=25).contains(&day) => {
-                run_days(vec![day]);
+    };
+    let comparisons = baseline::compare(runs, &baseline, options.threshold);
+    header(out, " BASELINE ");
+    for c in &comparisons {
+        let color = if c.regressed { TEXT_RED } else { "" };
+        writeln!(
+            out,
+            "{color}{:<18}{:<6}|{:>13} ->{:>13} | {:+7.1}%{TEXT_RESET}",
+            format!("Day {}/{}", c.day, c.input),
+            c.phase,
+            format!("{:?}", c.old),
+            format!("{:?}", c.new),
+            c.change()
+        )
+        .unwrap();
+    }
+    let regressions = comparisons.iter().filter(|c| c.regressed).count();
+    if regressions == 0 {
+        writeln!(
+            out,
+            "No phase got more than {}% slower than the baseline.",
+            options.threshold
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            out,
+            "{TEXT_RED}{regressions} phases got more than {}% slower than the baseline.{TEXT_RESET}",
+            options.threshold
+        )
+        .unwrap();
+    }
+    regressions == 0
+}
+
+/// Loads the stored results, warning about anything wrong with them.
+fn load_results(out: &mut String, options: &Options) -> HashMap<usize, DayResults> {
+    match results::load(&options.results) {
+        Ok((results, problems)) => {
+            if !problems.is_empty() {
+                writeln!(
+                    out,
+                    "{TEXT_YELLOW}Problems in {}:{TEXT_RESET}",
+                    options.results.display()
+                )
+                .unwrap();
+                for problem in problems {
+                    writeln!(out, "  {problem}").unwrap();
+                }
             }
-            _ => panic!("Wrong day passed"),
+            results
+        }
+        Err(e) => {
+            writeln!(
+                out,
+                "{TEXT_YELLOW}Can't load {} file:{TEXT_RESET}\n  {e:?}",
+                options.results.display()
+            )
+            .unwrap();
+            HashMap::new()
+        }
+    }
+}
+
+/// How many days to run at the same time. Only one when counting
+/// allocations, which are counted for the whole process.
+fn jobs(out: &mut String, options: &Options) -> usize {
+    if memory::COUNTING && options.jobs > 1 {
+        writeln!(
+            out,
+            "{TEXT_YELLOW}Counting allocations, running one day at a time instead of {}{TEXT_RESET}",
+            options.jobs
+        )
+        .unwrap();
+        1
+    } else {
+        options.jobs
+    }
+}
+
+fn run_days(options: &Options) -> ExitCode {
+    let mut runs = Vec::new();
+    let mut summary = Summary::default();
+    let mut out = String::new();
+
+    let results = load_results(&mut out, options);
+    if !options.variants.is_empty()
+        && options
+            .days
+            .iter()
+            .all(|&day| registry::select(day, &options.variants).is_empty())
+    {
+        eprintln!(
+            "{TEXT_RED}Error:{TEXT_RESET} None of the days has a variant named {}, see --list",
+            options.variants.join(" or ")
+        );
+        return ExitCode::from(2);
+    }
+    for param in &options.params {
+        let days = options.days.iter();
+        if !days
+            .flat_map(|&day| registry::select(day, &options.variants))
+            .any(|runner| overrides(param, runner))
+        {
+            eprintln!(
+                "{TEXT_RED}Error:{TEXT_RESET} None of the days has a parameter named {}, see --list",
+                param.name
+            );
+            return ExitCode::from(2);
+        }
+    }
+    let jobs = jobs(&mut out, options);
+    emit(options, &mut out);
+    schedule::run_ordered(
+        &options.days,
+        jobs,
+        |&day| {
+            let mut out = String::new();
+            let day_results = results.get(&(day as usize)).cloned().unwrap_or_default();
+            let mut day_summary = Summary::default();
+            // Parts catch their own panics, this is for anything else going wrong.
+            let day_runs = isolate::catch(|| {
+                Ok(run_day(
+                    &mut out,
+                    day,
+                    options,
+                    &day_results,
+                    &mut day_summary,
+                ))
+            })
+            .unwrap_or_else(|e| {
+                writeln!(out, "{TEXT_RED}Day {day} failed:{TEXT_RESET} {e:?}").unwrap();
+                day_summary.failed += options.parts.iter().filter(|&&part| part).count();
+                vec![]
+            });
+            (out, day_runs, day_summary)
+        },
+        |(mut day_out, day_runs, day_summary)| {
+            emit(options, &mut day_out);
+            runs.extend(day_runs);
+            summary.add(day_summary);
         },
-        _ => panic!("Wrong parameters"),
+    );
+
+    print_timings(&mut out, &runs, options);
+    print_results_matrix(&mut out, &runs);
+    print_summary(&mut out, &summary);
+    if options.record {
+        record_answers(&mut out, options, &runs);
+    }
+    let mut success = summary.success();
+    if let Some(file) = &options.baseline {
+        success &= compare_baseline(&mut out, &runs, options, file);
+    }
+    if let Some(file) = &options.save_baseline {
+        match baseline::save(file, &runs) {
+            Ok(()) => writeln!(out, "Saved baseline to {}.", file.display()).unwrap(),
+            Err(e) => writeln!(
+                out,
+                "{TEXT_RED}Can't save baseline {}:{TEXT_RESET}\n  {e:?}",
+                file.display()
+            )
+            .unwrap(),
+        }
+    }
+    emit(options, &mut out);
+
+    match options.format {
+        Format::Text => {}
+        Format::Json => print!("{}", report::json(&runs)),
+        Format::Csv => print!("{}", report::csv(&runs)),
+        Format::Junit => print!("{}", report::junit(&runs)),
+    }
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn record_answers(out: &mut String, options: &Options, runs: &[InputRun]) {
+    let mut answers = vec![];
+    for run in runs {
+        for (part, part_run) in run.parts.iter().enumerate() {
+            let Some(part_run) = part_run else {
+                continue;
+            };
+            if run.variant != registry::DEFAULT_VARIANT {
+                continue;
+            }
+            if part_run.status == Status::Mismatch && !options.force {
+                writeln!(out,
+                    "{TEXT_YELLOW}Not overwriting stored answer for day {} part {} ({}), use --force to replace it{TEXT_RESET}",
+                    run.day,
+                    part + 1,
+                    run.input
+                ).unwrap();
+            } else if matches!(part_run.status, Status::New | Status::Mismatch) {
+                answers.push(results::NewAnswer {
+                    day: run.day,
+                    input: run.input.clone(),
+                    part,
+                    answer: part_run.answer.clone(),
+                });
+            }
+        }
+    }
+    if answers.is_empty() {
+        writeln!(out, "No new answers to record.").unwrap();
+        return;
+    }
+    match results::record(&options.results, &answers) {
+        Ok(()) => writeln!(
+            out,
+            "Recorded {} answers into {}.",
+            answers.len(),
+            options.results.display()
+        )
+        .unwrap(),
+        Err(e) => writeln!(
+            out,
+            "{TEXT_RED}Can't record answers into {}:{TEXT_RESET}\n  {e:?}",
+            options.results.display()
+        )
+        .unwrap(),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == isolate::CHILD_FLAG) {
+        return isolate::child_main(&args[1..]);
+    }
+    match cli::parse(args) {
+        Ok(Command::Run(options)) => run_days(&options),
+        Ok(Command::List(days)) => {
+            println!("Day | {:<12}| {:<9}| Parameters", "Name", "Variant");
+            for runner in registry::RUNNERS.iter().filter(|r| days.contains(&r.day)) {
+                let params = runner.default_params();
+                let params = params.iter().map(|(name, value)| format!("{name}={value}"));
+                println!(
+                    "{:^4}| {:<12}| {:<9}| {}",
+                    runner.day,
+                    runner.name,
+                    runner.variant,
+                    params.format(", ")
+                );
+            }
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{TEXT_RED}Error:{TEXT_RESET} {e}\n\n{}", cli::USAGE);
+            ExitCode::from(2)
+        }
     }
 }
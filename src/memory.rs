@@ -0,0 +1,125 @@
+use serde_json::{json, Value};
+
+/// Heap allocations made during a phase, counted by the global allocator
+/// that the `count-allocations` feature installs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Allocations {
+    /// Bytes allocated in total, counting every reallocation again.
+    pub bytes: u64,
+    pub count: u64,
+    /// Most bytes that were allocated at once, on top of what already was
+    /// before the phase.
+    pub peak: u64,
+}
+
+/// Whether [`measure`] counts allocations.
+pub const COUNTING: bool = cfg!(feature = "count-allocations");
+
+/// The allocations as in the JSON report, `null` when they weren't counted.
+pub fn to_json(allocations: Option<Allocations>) -> Value {
+    allocations.map_or(Value::Null, |allocations| {
+        json!({
+            "bytes": allocations.bytes,
+            "count": allocations.count,
+            "peak_bytes": allocations.peak,
+        })
+    })
+}
+
+#[cfg(feature = "count-allocations")]
+mod counting {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::{AtomicU64, Ordering::Relaxed},
+    };
+
+    pub static BYTES: AtomicU64 = AtomicU64::new(0);
+    pub static COUNT: AtomicU64 = AtomicU64::new(0);
+    pub static LIVE: AtomicU64 = AtomicU64::new(0);
+    pub static PEAK: AtomicU64 = AtomicU64::new(0);
+
+    /// The system allocator, counting what goes through it.
+    struct Counting;
+
+    fn allocated(size: usize) {
+        let size = size as u64;
+        BYTES.fetch_add(size, Relaxed);
+        COUNT.fetch_add(1, Relaxed);
+        let live = LIVE.fetch_add(size, Relaxed) + size;
+        PEAK.fetch_max(live, Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            LIVE.fetch_sub(layout.size() as u64, Relaxed);
+        }
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                LIVE.fetch_sub(layout.size() as u64, Relaxed);
+                allocated(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+}
+
+/// Runs `f`, counting its allocations when the `count-allocations` feature
+/// is enabled. The counts are of the whole process, so they are only exact
+/// while nothing else runs: days run one at a time then.
+#[cfg(feature = "count-allocations")]
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<Allocations>) {
+    use counting::*;
+    use std::sync::atomic::Ordering::Relaxed;
+    let live = LIVE.load(Relaxed);
+    PEAK.store(live, Relaxed);
+    let (bytes, count) = (BYTES.load(Relaxed), COUNT.load(Relaxed));
+    let result = f();
+    let allocations = Allocations {
+        bytes: BYTES.load(Relaxed) - bytes,
+        count: COUNT.load(Relaxed) - count,
+        peak: PEAK.load(Relaxed).saturating_sub(live),
+    };
+    (result, Some(allocations))
+}
+#[cfg(not(feature = "count-allocations"))]
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<Allocations>) {
+    (f(), None)
+}
+
+#[cfg(all(test, feature = "count-allocations"))]
+mod tests {
+    use super::*;
+    #[test]
+    fn counts() {
+        let (_, allocations) = measure(|| {
+            let mut vec = std::hint::black_box(vec![0_u8; 1000]);
+            vec.extend([1; 1000]);
+            drop(vec);
+            drop(std::hint::black_box(vec![0_u8; 100]));
+        });
+        let allocations = allocations.unwrap();
+        // Other tests allocate and free at the same time, which leaves the
+        // peak unknown.
+        assert!(allocations.bytes >= 3100);
+        assert!(allocations.count >= 3);
+    }
+}
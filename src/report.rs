@@ -0,0 +1,469 @@
+use crate::{
+    answer::Answer,
+    bench::Stats,
+    days::registry::DEFAULT_VARIANT,
+    memory::{self, Allocations},
+    results::DEFAULT_INPUT,
+};
+use itertools::Itertools;
+use serde_json::{json, Value};
+use std::{fmt::Write, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Match,
+    Mismatch,
+    New,
+    /// The part returned an error, or its input couldn't be parsed.
+    Failed,
+    /// The part didn't finish before the `--timeout`.
+    TimedOut,
+}
+impl Status {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Match => "match",
+            Self::Mismatch => "mismatch",
+            Self::New => "new",
+            Self::Failed => "failed",
+            Self::TimedOut => "timeout",
+        }
+    }
+    /// Whether the part ran to its end and gave an answer.
+    pub const fn finished(self) -> bool {
+        !matches!(self, Self::Failed | Self::TimedOut)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartRun {
+    /// The answer, or the error chain as text when the part failed.
+    pub answer: Answer,
+    pub expected: Option<Answer>,
+    /// Zero when the part was solved together with the other one, see
+    /// [`InputRun::both_time`].
+    pub time: Duration,
+    /// Time spent copying the parsed input for the part, only for days whose
+    /// parts consume it. Not included in `time`.
+    pub clone_time: Option<Duration>,
+    pub stats: Option<Stats>,
+    /// Only counted with the `count-allocations` feature, and not when the
+    /// part was solved together with the other one.
+    pub allocations: Option<Allocations>,
+    pub status: Status,
+}
+
+#[derive(Debug, Clone)]
+pub struct InputRun {
+    pub day: u8,
+    pub input: String,
+    pub variant: &'static str,
+    pub parse_time: Duration,
+    pub parse_stats: Option<Stats>,
+    pub parse_allocations: Option<Allocations>,
+    pub parts: [Option<PartRun>; 2],
+    /// Time of solving both parts in one go, for days that share work
+    /// between them.
+    pub both_time: Option<Duration>,
+    pub both_stats: Option<Stats>,
+    pub both_allocations: Option<Allocations>,
+}
+impl InputRun {
+    /// The day, followed by the input and variant when they aren't the
+    /// default ones, like `11`, `11/alice` or `11/alice:queue`.
+    pub fn label(&self) -> String {
+        let mut label = self.day.to_string();
+        if self.input != DEFAULT_INPUT {
+            write!(label, "/{}", self.input).unwrap();
+        }
+        if self.variant != DEFAULT_VARIANT {
+            write!(label, ":{}", self.variant).unwrap();
+        }
+        label
+    }
+    /// The input name, followed by the variant unless it is the default one.
+    pub fn key(&self) -> String {
+        if self.variant == DEFAULT_VARIANT {
+            self.input.clone()
+        } else {
+            format!("{}:{}", self.input, self.variant)
+        }
+    }
+    /// Time of part 1 or 2 for `part` 0 or 1, unless it didn't run or was
+    /// solved together with the other part.
+    pub fn part_time(&self, part: usize) -> Option<Duration> {
+        let part = self.parts[part].as_ref()?;
+        self.both_time.is_none().then_some(part.time)
+    }
+    /// Timings of every phase that ran, named `parse`, `part1` and `part2`,
+    /// or `both` when the parts were solved together, and `clone1` and
+    /// `clone2` for the copies of consumed parsed inputs.
+    pub fn phases(&self) -> impl Iterator<Item = (&'static str, Duration)> + '_ {
+        let parts = [("part1", "clone1"), ("part2", "clone2")]
+            .into_iter()
+            .enumerate()
+            .flat_map(move |(i, (part_name, clone_name))| {
+                let clone = self.parts[i].as_ref().and_then(|part| part.clone_time);
+                let clone = clone.map(|time| (clone_name, time));
+                clone
+                    .into_iter()
+                    .chain(self.part_time(i).map(|time| (part_name, time)))
+            });
+        std::iter::once(("parse", self.parse_time))
+            .chain(parts)
+            .chain(self.both_time.map(|time| ("both", time)))
+    }
+    pub fn total_time(&self) -> Duration {
+        self.phases().map(|(_, time)| time).sum()
+    }
+}
+
+/// Number of parts by outcome, over a whole run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub passed: usize,
+    pub mismatched: usize,
+    pub new: usize,
+    pub failed: usize,
+    pub timed_out: usize,
+    pub missing_input: usize,
+    /// Parts where a variant found another answer than the first variant.
+    pub disagreeing: usize,
+}
+impl Summary {
+    pub const fn count(&mut self, status: Status) {
+        match status {
+            Status::Match => self.passed += 1,
+            Status::Mismatch => self.mismatched += 1,
+            Status::New => self.new += 1,
+            Status::Failed => self.failed += 1,
+            Status::TimedOut => self.timed_out += 1,
+        }
+    }
+    pub const fn add(&mut self, other: Self) {
+        self.passed += other.passed;
+        self.mismatched += other.mismatched;
+        self.new += other.new;
+        self.failed += other.failed;
+        self.timed_out += other.timed_out;
+        self.missing_input += other.missing_input;
+        self.disagreeing += other.disagreeing;
+    }
+    pub const fn success(&self) -> bool {
+        self.mismatched == 0 && self.failed == 0 && self.timed_out == 0 && self.disagreeing == 0
+    }
+}
+
+/// The stored answer of a part as text, empty when there is none.
+fn expected(part: &PartRun) -> String {
+    part.expected
+        .as_ref()
+        .map(Answer::to_string)
+        .unwrap_or_default()
+}
+
+/// JSON numbers don't fit `u128`, and no run takes 584 years.
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+fn stats_json(stats: Option<&Stats>) -> Value {
+    stats.map_or(Value::Null, |stats| {
+        json!({
+            "samples": stats.samples,
+            "batch": stats.batch,
+            "min_ns": nanos(stats.min),
+            "median_ns": nanos(stats.median),
+            "mean_ns": nanos(stats.mean),
+            "p95_ns": nanos(stats.p95),
+            "stddev_ns": nanos(stats.stddev),
+        })
+    })
+}
+
+pub fn json(runs: &[InputRun]) -> String {
+    let runs: Vec<_> = runs
+        .iter()
+        .map(|run| {
+            let parts: Vec<_> = run
+                .parts
+                .iter()
+                .enumerate()
+                .filter_map(|(i, part)| {
+                    let part = part.as_ref()?;
+                    Some(json!({
+                        "part": i + 1,
+                        "time_ns": nanos(part.time),
+                        "clone_ns": part.clone_time.map(nanos),
+                        "stats": stats_json(part.stats.as_ref()),
+                        "allocations": memory::to_json(part.allocations),
+                        "answer": part.answer.to_string(),
+                        "expected": part.expected.as_ref().map(Answer::to_string),
+                        "status": part.status.name(),
+                    }))
+                })
+                .collect();
+            json!({
+                "day": run.day,
+                "input": run.input,
+                "variant": run.variant,
+                "parse": {
+                    "time_ns": nanos(run.parse_time),
+                    "stats": stats_json(run.parse_stats.as_ref()),
+                    "allocations": memory::to_json(run.parse_allocations),
+                },
+                "parts": parts,
+                "both": run.both_time.map(|time| json!({
+                    "time_ns": nanos(time),
+                    "stats": stats_json(run.both_stats.as_ref()),
+                    "allocations": memory::to_json(run.both_allocations),
+                })),
+            })
+        })
+        .collect();
+    let mut out = serde_json::to_string_pretty(&json!({ "runs": runs })).unwrap();
+    out.push('\n');
+    out
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// One row per day and input, with the durations in nanoseconds. The
+/// allocations of the phases come last, empty unless they were counted.
+pub fn csv(runs: &[InputRun]) -> String {
+    let mut out = String::from(
+        "day,input,variant,parse_ns,part1_ns,part1_clone_ns,part1_answer,part1_expected,\
+         part1_status,part2_ns,part2_clone_ns,part2_answer,part2_expected,part2_status,both_ns,\
+         parse_alloc_bytes,parse_allocs,parse_peak_bytes,part1_alloc_bytes,part1_allocs,\
+         part1_peak_bytes,part2_alloc_bytes,part2_allocs,part2_peak_bytes,both_alloc_bytes,\
+         both_allocs,both_peak_bytes\n",
+    );
+    for run in runs {
+        write!(
+            out,
+            "{},{},{},{}",
+            run.day,
+            csv_field(&run.input),
+            csv_field(run.variant),
+            nanos(run.parse_time)
+        )
+        .unwrap();
+        for part in &run.parts {
+            match part {
+                Some(part) => write!(
+                    out,
+                    ",{},{},{},{},{}",
+                    nanos(part.time),
+                    part.clone_time
+                        .map(nanos)
+                        .map_or_else(String::new, |n| n.to_string()),
+                    csv_field(&part.answer.to_string()),
+                    csv_field(&expected(part)),
+                    part.status.name()
+                )
+                .unwrap(),
+                None => out.push_str(",,,,,"),
+            }
+        }
+        if let Some(time) = run.both_time {
+            write!(out, ",{}", nanos(time)).unwrap();
+        } else {
+            out.push(',');
+        }
+        let part_allocations = |i: usize| run.parts[i].as_ref()?.allocations;
+        for allocations in [
+            run.parse_allocations,
+            part_allocations(0),
+            part_allocations(1),
+            run.both_allocations,
+        ] {
+            match allocations {
+                Some(a) => write!(out, ",{},{},{}", a.bytes, a.count, a.peak).unwrap(),
+                None => out.push_str(",,,"),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// One test suite per day, with a test case for parsing and each part of
+/// every input, and one for solving both parts together where that
+/// happened. Mismatching answers are reported as failures, and parts that
+/// failed to run or timed out as errors.
+pub fn junit(runs: &[InputRun]) -> String {
+    let mut suites = String::new();
+    let (mut total_tests, mut total_failures, mut total_errors) = (0, 0, 0);
+    for (day, day_runs) in &runs.iter().group_by(|run| run.day) {
+        let day_runs: Vec<_> = day_runs.collect();
+        let mut cases = String::new();
+        let (mut tests, mut failures, mut errors) = (0, 0, 0);
+        for run in &day_runs {
+            let class = xml_escape(&format!("day{day}.{}", run.key()));
+            tests += 1;
+            writeln!(
+                cases,
+                "    <testcase classname=\"{class}\" name=\"parse\" time=\"{:.6}\"/>",
+                run.parse_time.as_secs_f64()
+            )
+            .unwrap();
+            for (i, part) in run.parts.iter().enumerate() {
+                let Some(part) = part else {
+                    continue;
+                };
+                tests += 1;
+                writeln!(
+                    cases,
+                    "    <testcase classname=\"{class}\" name=\"part{}\" time=\"{:.6}\">",
+                    i + 1,
+                    part.time.as_secs_f64()
+                )
+                .unwrap();
+                match part.status {
+                    Status::Mismatch => {
+                        failures += 1;
+                        writeln!(
+                            cases,
+                            "      <failure message=\"Result does not match stored result\">Expected:\n{}\nGot:\n{}</failure>",
+                            xml_escape(&expected(part)),
+                            xml_escape(&part.answer.to_string())
+                        )
+                        .unwrap();
+                    }
+                    Status::Failed | Status::TimedOut => {
+                        errors += 1;
+                        let message = if part.status == Status::Failed {
+                            "Part failed"
+                        } else {
+                            "Part timed out"
+                        };
+                        writeln!(
+                            cases,
+                            "      <error message=\"{message}\">{}</error>",
+                            xml_escape(&part.answer.to_string())
+                        )
+                        .unwrap();
+                    }
+                    Status::Match | Status::New => {}
+                }
+                writeln!(
+                    cases,
+                    "      <system-out>{}</system-out>\n    </testcase>",
+                    xml_escape(&part.answer.to_string())
+                )
+                .unwrap();
+            }
+            if let Some(time) = run.both_time {
+                tests += 1;
+                writeln!(
+                    cases,
+                    "    <testcase classname=\"{class}\" name=\"both\" time=\"{:.6}\"/>",
+                    time.as_secs_f64()
+                )
+                .unwrap();
+            }
+        }
+        let time: Duration = day_runs.iter().map(|run| run.total_time()).sum();
+        writeln!(
+            suites,
+            "  <testsuite name=\"day{day}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{:.6}\">\n{cases}  </testsuite>",
+            time.as_secs_f64()
+        )
+        .unwrap();
+        total_tests += tests;
+        total_failures += failures;
+        total_errors += errors;
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <testsuites name=\"advent-2022\" tests=\"{total_tests}\" failures=\"{total_failures}\" errors=\"{total_errors}\">\n\
+         {suites}</testsuites>\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn runs() -> Vec<InputRun> {
+        let part = |answer: &str, expected: Option<&str>, status| PartRun {
+            answer: Answer::Text(answer.to_string()),
+            expected: expected.map(|expected| Answer::Text(expected.to_string())),
+            time: Duration::from_micros(5),
+            clone_time: None,
+            stats: None,
+            allocations: None,
+            status,
+        };
+        let mut consumed = part("a\"b", Some("c<d"), Status::Mismatch);
+        consumed.clone_time = Some(Duration::from_micros(1));
+        vec![InputRun {
+            day: 3,
+            input: "default".to_string(),
+            variant: DEFAULT_VARIANT,
+            parse_time: Duration::from_micros(2),
+            parse_stats: None,
+            parse_allocations: Some(Allocations {
+                bytes: 300,
+                count: 4,
+                peak: 200,
+            }),
+            parts: [
+                Some(part("1,2", Some("1,2"), Status::Match)),
+                Some(consumed),
+            ],
+            both_time: None,
+            both_stats: None,
+            both_allocations: None,
+        }]
+    }
+    #[test]
+    fn csv() {
+        assert_eq!(
+            super::csv(&runs()).lines().nth(1).unwrap(),
+            "3,default,default,2000,5000,,\"1,2\",\"1,2\",match,5000,1000,\"a\"\"b\",c<d,mismatch,,\
+             300,4,200,,,,,,,,,"
+        );
+    }
+    #[test]
+    fn junit() {
+        let junit = super::junit(&runs());
+        assert!(junit
+            .contains("<testsuites name=\"advent-2022\" tests=\"3\" failures=\"1\" errors=\"0\">"));
+        assert!(junit.contains("Expected:\nc&lt;d\nGot:\na&quot;b</failure>"));
+    }
+    #[test]
+    fn json() {
+        let json: Value = serde_json::from_str(&super::json(&runs())).unwrap();
+        assert_eq!(json["runs"][0]["parts"][1]["status"], "mismatch");
+        assert_eq!(json["runs"][0]["parse"]["time_ns"], 2000);
+        assert_eq!(json["runs"][0]["parts"][0]["clone_ns"], Value::Null);
+        assert_eq!(json["runs"][0]["parts"][1]["clone_ns"], 1000);
+        assert_eq!(json["runs"][0]["parse"]["allocations"]["peak_bytes"], 200);
+        assert_eq!(json["runs"][0]["parts"][0]["allocations"], Value::Null);
+    }
+    #[test]
+    fn phases() {
+        let runs = runs();
+        let phases: Vec<_> = runs[0].phases().map(|(name, _)| name).collect();
+        assert_eq!(phases, ["parse", "part1", "clone2", "part2"]);
+        assert_eq!(runs[0].total_time(), Duration::from_micros(13));
+        let mut runs = runs;
+        runs[0].both_time = Some(Duration::from_micros(7));
+        let phases: Vec<_> = runs[0].phases().map(|(name, _)| name).collect();
+        assert_eq!(phases, ["parse", "clone2", "both"]);
+        assert_eq!(runs[0].total_time(), Duration::from_micros(10));
+    }
+}
@@ -1,44 +1,631 @@
+use crate::answer::Answer;
 use anyhow::{Context, Result};
-use std::{collections::HashMap, fs};
+use std::{collections::HashMap, fmt, fs, path::Path};
 
 use itertools::Itertools;
-use serde_json::Value;
-
-pub fn load() -> Result<HashMap<usize, [Option<String>; 2]>> {
-    let data = fs::read_to_string("results.json")?;
-    let v: Value = serde_json::from_str(&data)?;
-    let mut days = HashMap::new();
-    for (day, results) in v.as_object().context("Invalid results file")? {
-        let day = day.parse::<usize>()?;
-
-        let results: Vec<String> = results
-            .as_array()
-            .context(format!("Invalid results file for day {day}"))?
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+
+/// Name of the input read from `input/<day>.txt`, and of the results stored
+/// directly as an array under a day.
+pub const DEFAULT_INPUT: &str = "default";
+
+pub type Expected = [Option<Answer>; 2];
+pub type DayResults = HashMap<String, InputResults>;
+
+/// What is stored for an input of a day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputResults {
+    pub expected: Expected,
+    /// Puzzle parameters the answers are for, by name.
+    pub params: Vec<(String, String)>,
+    pub hints: [Hints; 2],
+}
+
+/// Answers of a part that were rejected while solving it, for parts without
+/// a stored answer yet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hints {
+    pub wrong: Vec<Answer>,
+    pub too_high: Vec<i64>,
+    pub too_low: Vec<i64>,
+}
+impl Hints {
+    /// Why `answer` can't be right, if the hints tell.
+    pub fn check(&self, answer: &Answer) -> Option<String> {
+        let number = |&num| Answer::Number(num);
+        if self.wrong.iter().any(|wrong| answer.matches(wrong)) {
+            return Some("This answer was already rejected".to_string());
+        }
+        if self
+            .too_high
+            .iter()
+            .map(number)
+            .any(|high| answer.matches(&high))
+        {
+            return Some("This answer was already rejected as too high".to_string());
+        }
+        if self
+            .too_low
             .iter()
-            .filter_map(|result| match result {
-                Value::Bool(bool) => Some(bool.to_string()),
-                Value::Number(num) => Some(num.to_string()),
-                Value::String(str) => Some(str.clone()),
-                Value::Array(vec) => Some(
-                    vec.iter()
-                        .map(|v| {
-                            if let Value::String(s) = v {
-                                s.clone()
-                            } else {
-                                v.to_string()
-                            }
-                        })
-                        .join("\n"),
+            .map(number)
+            .any(|low| answer.matches(&low))
+        {
+            return Some("This answer was already rejected as too low".to_string());
+        }
+        let Answer::Number(answer) = answer else {
+            return None;
+        };
+        if let Some(high) = self.too_high.iter().min().filter(|&high| answer > high) {
+            return Some(format!("Too high, {high} already was"));
+        }
+        if let Some(low) = self.too_low.iter().max().filter(|&low| answer < low) {
+            return Some(format!("Too low, {low} already was"));
+        }
+        None
+    }
+}
+
+/// Something wrong with the results file, at a JSON path like
+/// `$.15.example.answers[1]`. The rest of the file is still used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub path: String,
+    pub message: String,
+}
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// A JSON value that keeps every entry of its objects, unlike [`Value`], so
+/// that duplicate keys can be reported.
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf(Value),
+    Array(Vec<Self>),
+    Object(Vec<(String, Self)>),
+}
+impl Node {
+    const fn kind(&self) -> &'static str {
+        match self {
+            Self::Leaf(Value::Null) => "null",
+            Self::Leaf(Value::Bool(_)) => "a boolean",
+            Self::Leaf(Value::Number(_)) => "a number",
+            Self::Leaf(Value::String(_)) => "a string",
+            Self::Leaf(_) | Self::Array(_) => "an array",
+            Self::Object(_) => "an object",
+        }
+    }
+}
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+struct NodeVisitor;
+impl<'de> Visitor<'de> for NodeVisitor {
+    type Value = Node;
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a JSON value")
+    }
+    fn visit_unit<E>(self) -> Result<Node, E> {
+        Ok(Node::Leaf(Value::Null))
+    }
+    fn visit_bool<E>(self, v: bool) -> Result<Node, E> {
+        Ok(Node::Leaf(Value::from(v)))
+    }
+    fn visit_i64<E>(self, v: i64) -> Result<Node, E> {
+        Ok(Node::Leaf(Value::from(v)))
+    }
+    fn visit_u64<E>(self, v: u64) -> Result<Node, E> {
+        Ok(Node::Leaf(Value::from(v)))
+    }
+    fn visit_f64<E>(self, v: f64) -> Result<Node, E> {
+        Ok(Node::Leaf(Value::from(v)))
+    }
+    fn visit_str<E>(self, v: &str) -> Result<Node, E> {
+        Ok(Node::Leaf(Value::from(v)))
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Node, A::Error> {
+        let mut items = vec![];
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Node::Array(items))
+    }
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Node, A::Error> {
+        let mut entries = vec![];
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Node::Object(entries))
+    }
+}
+
+/// Checks stored results while loading them, collecting a [`Problem`] for
+/// everything that isn't used as it was meant.
+#[derive(Default)]
+struct Loader {
+    problems: Vec<Problem>,
+}
+impl Loader {
+    fn problem(&mut self, path: &str, message: impl Into<String>) {
+        self.problems.push(Problem {
+            path: path.to_string(),
+            message: message.into(),
+        });
+    }
+    /// The entries of an object, reporting duplicate keys and keeping the
+    /// last entry of each.
+    fn entries<'a>(
+        &mut self,
+        path: &str,
+        entries: &'a [(String, Node)],
+    ) -> Vec<(String, &'a Node)> {
+        let mut unique: Vec<(String, &Node)> = vec![];
+        for (key, node) in entries {
+            let key_path = key_path(path, key);
+            if let Some(entry) = unique.iter_mut().find(|(known, _)| known == key) {
+                self.problem(&key_path, "Duplicate key, only the last one is used");
+                entry.1 = node;
+            } else {
+                unique.push((key.clone(), node));
+            }
+        }
+        unique
+    }
+
+    fn days(&mut self, root: &Node) -> HashMap<usize, DayResults> {
+        let mut days = HashMap::new();
+        let Node::Object(entries) = root else {
+            self.problem(
+                "$",
+                format!("Expected an object of days, found {}", root.kind()),
+            );
+            return days;
+        };
+        for (key, node) in self.entries("$", entries) {
+            let path = key_path("$", &key);
+            let Ok(day) = key.parse::<usize>() else {
+                self.problem(&path, "Expected a day number as key");
+                continue;
+            };
+            if !(1..=25).contains(&day) {
+                self.problem(
+                    &path,
+                    format!("Day {day} is outside 1..=25, so it never runs"),
+                );
+                continue;
+            }
+            let inputs = match node {
+                Node::Object(inputs) => self
+                    .entries(&path, inputs)
+                    .into_iter()
+                    .filter_map(|(name, node)| {
+                        let input = self.input(&key_path(&path, &name), node)?;
+                        Some((name, input))
+                    })
+                    .collect(),
+                node => self
+                    .input(&path, node)
+                    .map(|input| (DEFAULT_INPUT.to_string(), input))
+                    .into_iter()
+                    .collect(),
+            };
+            days.insert(day, inputs);
+        }
+        days
+    }
+
+    /// An array of answers, or an object of answers and parameters.
+    fn input(&mut self, path: &str, node: &Node) -> Option<InputResults> {
+        let entries = match node {
+            Node::Array(_) => {
+                return Some(InputResults {
+                    expected: self.answers(path, node),
+                    ..InputResults::default()
+                })
+            }
+            Node::Object(entries) => self.entries(path, entries),
+            node @ Node::Leaf(_) => {
+                self.problem(
+                    path,
+                    format!(
+                        "Expected an array of answers or an object, found {}",
+                        node.kind()
+                    ),
+                );
+                return None;
+            }
+        };
+        let mut input = InputResults::default();
+        for (key, node) in entries {
+            let path = key_path(path, &key);
+            match &key[..] {
+                "answers" => input.expected = self.answers(&path, node),
+                "params" => input.params = self.params(&path, node),
+                "hints" => input.hints = self.hints(&path, node),
+                _ => self.problem(
+                    &path,
+                    "Unknown key, expected \"answers\", \"params\" or \"hints\"",
                 ),
-                _ => None,
+            }
+        }
+        Some(input)
+    }
+
+    /// Up to two answers, `null` for a part without a known answer.
+    fn answers(&mut self, path: &str, node: &Node) -> Expected {
+        let mut expected = Expected::default();
+        let Node::Array(parts) = node else {
+            self.problem(
+                path,
+                format!("Expected an array of answers, found {}", node.kind()),
+            );
+            return expected;
+        };
+        for (i, part) in parts.iter().enumerate() {
+            let path = format!("{path}[{i}]");
+            if i >= expected.len() {
+                self.problem(&path, "There are only two parts, ignoring this answer");
+                continue;
+            }
+            expected[i] = self.answer(&path, part);
+        }
+        expected
+    }
+    /// A number, a string, or an array of lines for a grid.
+    fn answer(&mut self, path: &str, node: &Node) -> Option<Answer> {
+        match node {
+            Node::Leaf(Value::Null) => None,
+            Node::Leaf(value @ Value::Number(num)) if num.is_i64() || num.is_u64() => {
+                Answer::from_json(value)
+            }
+            Node::Leaf(value @ Value::String(_)) => Answer::from_json(value),
+            Node::Array(lines) => {
+                let mut rows = vec![];
+                for (i, line) in lines.iter().enumerate() {
+                    match line {
+                        Node::Leaf(Value::String(line)) => rows.push(line.clone()),
+                        line => self.problem(
+                            &format!("{path}[{i}]"),
+                            format!("Expected a line of text, found {}", line.kind()),
+                        ),
+                    }
+                }
+                Some(Answer::Grid(rows))
+            }
+            node => {
+                self.problem(
+                    path,
+                    format!(
+                        "Expected a whole number, a string, an array of lines or null, found {}",
+                        node.kind()
+                    ),
+                );
+                None
+            }
+        }
+    }
+    /// Up to two objects of hints, `null` for a part without any.
+    fn hints(&mut self, path: &str, node: &Node) -> [Hints; 2] {
+        let mut hints = <[Hints; 2]>::default();
+        let Node::Array(parts) = node else {
+            self.problem(
+                path,
+                format!("Expected an array of hints, found {}", node.kind()),
+            );
+            return hints;
+        };
+        for (i, part) in parts.iter().enumerate() {
+            let path = format!("{path}[{i}]");
+            let entries = match part {
+                _ if i >= hints.len() => {
+                    self.problem(&path, "There are only two parts, ignoring these hints");
+                    continue;
+                }
+                Node::Leaf(Value::Null) => continue,
+                Node::Object(entries) => self.entries(&path, entries),
+                part => {
+                    self.problem(
+                        &path,
+                        format!("Expected an object of hints, found {}", part.kind()),
+                    );
+                    continue;
+                }
+            };
+            for (key, node) in entries {
+                let path = key_path(&path, &key);
+                let Node::Array(answers) = node else {
+                    self.problem(
+                        &path,
+                        format!("Expected an array of answers, found {}", node.kind()),
+                    );
+                    continue;
+                };
+                let answers = answers.iter().enumerate();
+                match &key[..] {
+                    "wrong" => {
+                        hints[i].wrong = answers
+                            .filter_map(|(j, answer)| self.answer(&format!("{path}[{j}]"), answer))
+                            .collect();
+                    }
+                    "too_high" | "too_low" => {
+                        let bounds = answers
+                            .filter_map(|(j, answer)| match answer {
+                                Node::Leaf(Value::Number(num)) if num.is_i64() => num.as_i64(),
+                                answer => {
+                                    self.problem(
+                                        &format!("{path}[{j}]"),
+                                        format!("Expected a whole number, found {}", answer.kind()),
+                                    );
+                                    None
+                                }
+                            })
+                            .collect();
+                        if key == "too_high" {
+                            hints[i].too_high = bounds;
+                        } else {
+                            hints[i].too_low = bounds;
+                        }
+                    }
+                    _ => self.problem(
+                        &path,
+                        "Unknown key, expected \"wrong\", \"too_high\" or \"too_low\"",
+                    ),
+                }
+            }
+        }
+        hints
+    }
+    fn params(&mut self, path: &str, node: &Node) -> Vec<(String, String)> {
+        let Node::Object(entries) = node else {
+            self.problem(
+                path,
+                format!("Expected an object of parameters, found {}", node.kind()),
+            );
+            return vec![];
+        };
+        self.entries(path, entries)
+            .into_iter()
+            .filter_map(|(name, node)| match node {
+                Node::Leaf(Value::String(value)) => Some((name, value.clone())),
+                Node::Leaf(value @ (Value::Number(_) | Value::Bool(_))) => {
+                    Some((name, value.to_string()))
+                }
+                node => {
+                    self.problem(
+                        &key_path(path, &name),
+                        format!(
+                            "Expected a number, a string or a boolean, found {}",
+                            node.kind()
+                        ),
+                    );
+                    None
+                }
             })
-            .collect();
-        if results.len() == 2 {
-            days.insert(day, [Some(results[0].clone()), Some(results[1].clone())]);
-        } else if results.len() == 1 {
-            days.insert(day, [Some(results[0].clone()), None]);
+            .collect()
+    }
+}
+
+/// The JSON path of an entry of the object at `path`.
+fn key_path(path: &str, key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        format!("{path}.{key}")
+    } else {
+        format!("{path}[{key:?}]")
+    }
+}
+
+/// Loads stored results, either as an array of answers per day, or as an
+/// object of answer arrays keyed by input name:
+/// `{ "1": [1, 2], "2": { "alice": [3, 4], "bob": [null, 5] } }`
+///
+/// Inputs that need other puzzle parameters store them next to the answers:
+/// `{ "15": { "example": { "params": { "row": 10 }, "answers": [26] } } }`
+///
+/// The answers rejected for a part can be kept the same way, while working
+/// on it: `{ "16": { "default": { "hints": [{ "wrong": [1], "too_high": [2000] }] } } }`
+///
+/// Fails only when the file can't be read or isn't JSON. Everything else
+/// that is wrong is skipped, and returned as problems.
+pub fn load(file: &Path) -> Result<(HashMap<usize, DayResults>, Vec<Problem>)> {
+    parse(&fs::read_to_string(file)?)
+}
+
+fn parse(data: &str) -> Result<(HashMap<usize, DayResults>, Vec<Problem>)> {
+    let root: Node = serde_json::from_str(data)?;
+    let mut loader = Loader::default();
+    let days = loader.days(&root);
+    Ok((days, loader.problems))
+}
+
+/// A newly computed answer to write back into the results file.
+#[derive(Debug, Clone)]
+pub struct NewAnswer {
+    pub day: u8,
+    pub input: String,
+    pub part: usize,
+    pub answer: Answer,
+}
+
+/// Merges answers into the results file, creating it if needed. Answers are
+/// stored by kind, see [`Answer::to_json`].
+pub fn record(file: &Path, answers: &[NewAnswer]) -> Result<()> {
+    let mut root = if file.exists() {
+        serde_json::from_str(&fs::read_to_string(file)?)?
+    } else {
+        Value::Object(Map::new())
+    };
+    let days = root.as_object_mut().context("Invalid results file")?;
+    for answer in answers {
+        let day = days
+            .entry(answer.day.to_string())
+            .or_insert_with(|| Value::Array(vec![]));
+        if answer.input != DEFAULT_INPUT && day.is_array() {
+            let default = std::mem::take(day);
+            let mut inputs = Map::new();
+            if default.as_array().is_some_and(|parts| !parts.is_empty()) {
+                inputs.insert(DEFAULT_INPUT.to_string(), default);
+            }
+            *day = Value::Object(inputs);
+        }
+        let input = match day {
+            Value::Object(inputs) => inputs
+                .entry(answer.input.clone())
+                .or_insert_with(|| Value::Array(vec![])),
+            day => day,
+        };
+        let parts = match input {
+            Value::Object(input) => input
+                .entry("answers")
+                .or_insert_with(|| Value::Array(vec![])),
+            input => input,
+        }
+        .as_array_mut()
+        .context(format!("Invalid results file for day {}", answer.day))?;
+
+        // Parts before it without an answer are stored as null.
+        if parts.len() <= answer.part {
+            parts.resize(answer.part + 1, Value::Null);
         }
+        parts[answer.part] = answer.answer.to_json();
     }
+    let mut out = String::new();
+    write_pretty(&root, 0, false, &mut out);
+    out.push('\n');
+    fs::write(file, out)?;
+    Ok(())
+}
+
+/// Pretty printer keeping answer arrays of numbers and strings on one line,
+/// while arrays of lines get one line each, like in `results_example.json`.
+fn write_pretty(value: &Value, indent: usize, in_array: bool, out: &mut String) {
+    let pad = |out: &mut String, indent: usize| out.push_str(&" ".repeat(indent * 2));
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            out.push_str("{\n");
+            for (i, (key, value)) in map.iter().enumerate() {
+                pad(out, indent + 1);
+                out.push_str(&Value::from(key.as_str()).to_string());
+                out.push_str(": ");
+                write_pretty(value, indent + 1, false, out);
+                out.push_str(if i + 1 < map.len() { ",\n" } else { "\n" });
+            }
+            pad(out, indent);
+            out.push('}');
+        }
+        Value::Array(vec)
+            if !vec.is_empty()
+                && (in_array || vec.iter().any(|v| v.is_array() || v.is_object())) =>
+        {
+            out.push_str("[\n");
+            for (i, value) in vec.iter().enumerate() {
+                pad(out, indent + 1);
+                write_pretty(value, indent + 1, true, out);
+                out.push_str(if i + 1 < vec.len() { ",\n" } else { "\n" });
+            }
+            pad(out, indent);
+            out.push(']');
+        }
+        Value::Array(vec) => {
+            out.push('[');
+            out.push_str(&vec.iter().map(Value::to_string).join(", "));
+            out.push(']');
+        }
+        value => out.push_str(&value.to_string()),
+    }
+}
 
-    Ok(days)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn problems() {
+        let (days, problems) = parse(
+            r##"{
+                "1": [1, null],
+                "2": { "alice": [null, "x", 3], "bob": { "answers": [true], "params": { "n": 2 } } },
+                "3": [["#.", ".#"]],
+                "3": [4, 5],
+                "26": [1],
+                "x y": []
+            }"##,
+        )
+        .unwrap();
+        let messages: Vec<_> = problems.iter().map(|p| &p.path[..]).collect();
+        assert_eq!(
+            messages,
+            [
+                "$.3",
+                "$.2.alice[2]",
+                "$.2.bob.answers[0]",
+                "$.26",
+                "$[\"x y\"]"
+            ]
+        );
+        assert_eq!(
+            days[&1]["default"].expected,
+            [Some(Answer::Number(1)), None]
+        );
+        let alice = &days[&2]["alice"].expected;
+        assert_eq!(alice, &[None, Some(Answer::Text("x".to_string()))]);
+        assert_eq!(days[&2]["bob"].params, [("n".to_string(), "2".to_string())]);
+        assert_eq!(days[&3]["default"].expected[0], Some(Answer::Number(4)));
+        assert!(!days.contains_key(&26));
+        assert!(parse("{ \"1\": [1, }").is_err());
+    }
+    #[test]
+    fn hints() {
+        let (days, problems) = parse(
+            r#"{ "1": { "default": { "hints": [null, { "wrong": [7, "x"], "too_high": [100, 90], "too_low": [10, "y"], "close": [] }] } } }"#,
+        )
+        .unwrap();
+        let paths: Vec<_> = problems.iter().map(|p| &p.path[..]).collect();
+        assert_eq!(
+            paths,
+            [
+                "$.1.default.hints[1].too_low[1]",
+                "$.1.default.hints[1].close"
+            ]
+        );
+        let [first, second] = &days[&1]["default"].hints;
+        assert_eq!(first, &Hints::default());
+        let check = |num| second.check(&Answer::Number(num));
+        assert_eq!(check(50), None);
+        assert!(check(7).unwrap().contains("rejected"));
+        assert!(check(90).unwrap().contains("too high"));
+        assert!(check(95).unwrap().starts_with("Too high, 90"));
+        assert!(check(5).unwrap().starts_with("Too low, 10"));
+        assert!(second.check(&Answer::Text("x".to_string())).is_some());
+    }
+    #[test]
+    fn record_second_part_alone() {
+        let file = std::env::temp_dir().join(format!("results_{}.json", std::process::id()));
+        let answer = |day, input: &str, part, answer| NewAnswer {
+            day,
+            input: input.to_string(),
+            part,
+            answer: Answer::Number(answer),
+        };
+        record(
+            &file,
+            &[answer(1, DEFAULT_INPUT, 1, 2), answer(2, "alice", 1, 4)],
+        )
+        .unwrap();
+        let (days, _) = parse(&fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(
+            days[&1]["default"].expected,
+            [None, Some(Answer::Number(2))]
+        );
+        assert_eq!(days[&2]["alice"].expected, [None, Some(Answer::Number(4))]);
+        record(&file, &[answer(1, DEFAULT_INPUT, 0, 1)]).unwrap();
+        let (days, _) = parse(&fs::read_to_string(&file).unwrap()).unwrap();
+        fs::remove_file(&file).unwrap();
+        let both = [Some(Answer::Number(1)), Some(Answer::Number(2))];
+        assert_eq!(days[&1]["default"].expected, both);
+    }
 }
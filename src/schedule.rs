@@ -0,0 +1,86 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Mutex, PoisonError,
+    },
+    thread,
+};
+
+/// Held while a timed phase runs with `--serial-timing`.
+static TIMING_LOCK: Mutex<()> = Mutex::new(());
+
+/// Runs `f`, making sure no other exclusive phase runs at the same time
+/// when `exclusive` is set.
+pub fn timed<R>(exclusive: bool, f: impl FnOnce() -> R) -> R {
+    let _guard = exclusive.then(|| TIMING_LOCK.lock().unwrap_or_else(PoisonError::into_inner));
+    f()
+}
+
+/// Runs `job` for every item on `jobs` threads, and hands the results to
+/// `done` in the order of the items. Every result is handed over as soon as
+/// it and the results of all earlier items are ready.
+pub fn run_ordered<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    job: impl Fn(&T) -> R + Sync,
+    mut done: impl FnMut(R),
+) {
+    if jobs <= 1 {
+        for item in items {
+            done(job(item));
+        }
+        return;
+    }
+    let (job, next) = (&job, &AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        // Every thread keeps taking the next item, so the items start in
+        // order. The threads aren't part of a rayon pool, so the parallel
+        // iterators of the days all run on the global pool, like they do
+        // with a single job.
+        for _ in 0..jobs.min(items.len()) {
+            let sender = sender.clone();
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+                if sender.send((i, job(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+        let mut pending = BTreeMap::new();
+        let mut next_done = 0;
+        for (i, result) in receiver {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&next_done) {
+                done(result);
+                next_done += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    #[test]
+    fn keeps_order() {
+        let items: Vec<u64> = (0..20).rev().collect();
+        let mut results = vec![];
+        run_ordered(
+            &items,
+            4,
+            |&i| {
+                thread::sleep(Duration::from_millis(i));
+                i
+            },
+            |i| results.push(i),
+        );
+        assert_eq!(results, items);
+    }
+}